@@ -0,0 +1,444 @@
+// src/board.rs
+//! A message board: notes sent to the running heart over a local socket.
+//!
+//! Clients write one JSON object per line, e.g.
+//! `{"text": "Be mine", "color": "#ff0066", "effect": "burst"}`, and get `ok`
+//! or `error: ...` back for each. Notes wait in a queue and are shown one at a
+//! time, each for its display duration.
+//!
+//! Only loopback TCP addresses and Unix domain sockets are accepted, so the
+//! board cannot be reached from other machines.
+
+use std::{
+    collections::VecDeque,
+    fmt,
+    io::{self, BufRead, BufReader, Read, Write},
+    net::{IpAddr, Ipv4Addr, SocketAddr, TcpListener, TcpStream},
+    path::PathBuf,
+    str::FromStr,
+    sync::{
+        Arc,
+        atomic::{AtomicUsize, Ordering},
+        mpsc::{self, Receiver, SyncSender, TrySendError},
+    },
+    thread,
+    time::Duration,
+};
+
+use color_eyre::{
+    Result,
+    eyre::{WrapErr, bail, ensure},
+};
+use ratatui::style::Color;
+use serde::{Deserialize, Deserializer};
+
+use crate::config::deserialize_duration;
+
+/// Longest note text in characters.
+pub const MAX_TEXT: usize = 280;
+/// Longest sender name in characters.
+pub const MAX_FROM: usize = 40;
+/// Most notes waiting to be shown; further ones are turned away.
+pub const MAX_QUEUE: usize = 32;
+/// Most clients connected at once; further ones are turned away.
+pub const MAX_CLIENTS: usize = 16;
+/// Longest line a client may send, in bytes.
+const MAX_LINE: usize = 4096;
+/// How long a client may stay silent before it is disconnected.
+const IDLE_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Where the board listens.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Address {
+    /// A TCP port on a loopback address.
+    Tcp(SocketAddr),
+    /// A Unix domain socket at this path.
+    #[cfg(unix)]
+    Unix(PathBuf),
+}
+
+impl FromStr for Address {
+    type Err = color_eyre::Report;
+
+    /// Parse a port (`7777`, on 127.0.0.1), a loopback `host:port`
+    /// (`localhost:7777`, `[::1]:7777`) or a socket path (`unix:heart.sock`,
+    /// `/tmp/heart.sock`).
+    fn from_str(s: &str) -> Result<Self> {
+        let s = s.trim();
+        if let Some(path) = s.strip_prefix("unix:") {
+            return unix(path);
+        }
+        if s.contains('/') {
+            return unix(s);
+        }
+        let addr = if let Ok(port) = s.parse::<u16>() {
+            SocketAddr::new(IpAddr::V4(Ipv4Addr::LOCALHOST), port)
+        } else if let Some(port) = s.strip_prefix("localhost:") {
+            let port = port
+                .parse()
+                .wrap_err_with(|| format!("`{port}` is not a port number"))?;
+            SocketAddr::new(IpAddr::V4(Ipv4Addr::LOCALHOST), port)
+        } else {
+            s.parse()
+                .wrap_err_with(|| format!("`{s}` is not a port, host:port or socket path"))?
+        };
+        ensure!(
+            addr.ip().is_loopback(),
+            "{addr} is not a loopback address; the board only listens on this machine"
+        );
+        Ok(Address::Tcp(addr))
+    }
+}
+
+#[cfg(unix)]
+fn unix(path: &str) -> Result<Address> {
+    ensure!(!path.is_empty(), "the socket path is empty");
+    Ok(Address::Unix(PathBuf::from(path)))
+}
+
+#[cfg(not(unix))]
+fn unix(path: &str) -> Result<Address> {
+    bail!("`{path}`: Unix domain sockets are not supported on this platform")
+}
+
+impl fmt::Display for Address {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Address::Tcp(addr) => write!(f, "{addr}"),
+            #[cfg(unix)]
+            Address::Unix(path) => write!(f, "unix:{}", path.display()),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for Address {
+    fn deserialize<D: Deserializer<'de>>(de: D) -> Result<Self, D::Error> {
+        let s = String::deserialize(de)?;
+        s.parse()
+            .map_err(|err| serde::de::Error::custom(format!("{err:#}")))
+    }
+}
+
+/// How a note makes its entrance.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum NoteEffect {
+    /// Shown in full from the start.
+    #[default]
+    None,
+    /// Typed out one character at a time.
+    Typewriter,
+    /// Faded in from the background.
+    FadeIn,
+    /// Hearts burst from the middle of the heart.
+    Burst,
+    /// Confetti is thrown from the top edge.
+    Confetti,
+}
+
+/// One message sent to the board.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Note {
+    /// What to show; control characters are dropped, line breaks kept.
+    #[serde(deserialize_with = "deserialize_text")]
+    pub text: String,
+    /// Who sent it, shown in the title of the overlay.
+    #[serde(default, deserialize_with = "deserialize_from")]
+    pub from: Option<String>,
+    /// Color of the text; the heart's current color when unset.
+    #[serde(default)]
+    pub color: Option<Color>,
+    /// How the note appears.
+    #[serde(default)]
+    pub effect: NoteEffect,
+    /// How long the note stays, e.g. `"5s"`; `note-duration` when unset.
+    #[serde(default, deserialize_with = "deserialize_duration")]
+    pub duration: Option<Duration>,
+}
+
+impl FromStr for Note {
+    type Err = color_eyre::Report;
+
+    /// Parse one JSON line as sent by a client.
+    fn from_str(s: &str) -> Result<Self> {
+        let note: Note = serde_json::from_str(s)?;
+        ensure!(!note.text.trim().is_empty(), "the text is empty");
+        ensure!(
+            note.duration.is_none_or(|d| !d.is_zero()),
+            "the duration must be longer than zero"
+        );
+        Ok(note)
+    }
+}
+
+fn deserialize_text<'de, D: Deserializer<'de>>(de: D) -> Result<String, D::Error> {
+    printable(&String::deserialize(de)?, "text", MAX_TEXT, true).map_err(serde::de::Error::custom)
+}
+
+fn deserialize_from<'de, D: Deserializer<'de>>(de: D) -> Result<Option<String>, D::Error> {
+    Option::<String>::deserialize(de)?
+        .map(|from| printable(&from, "from", MAX_FROM, false))
+        .transpose()
+        .map_err(serde::de::Error::custom)
+}
+
+/// `text` without control characters, which could otherwise smuggle escape
+/// sequences to the terminal, unless it is longer than `limit` characters.
+fn printable(text: &str, field: &str, limit: usize, lines: bool) -> Result<String, String> {
+    let count = text.chars().count();
+    if count > limit {
+        return Err(format!(
+            "`{field}` is {count} characters long, the limit is {limit}"
+        ));
+    }
+    Ok(text
+        .chars()
+        .filter(|&c| (lines && c == '\n') || !c.is_control())
+        .collect())
+}
+
+/// Notes waiting to be shown and the one on screen.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Board {
+    queue: VecDeque<Note>,
+    /// The note on screen and how many seconds it has been there.
+    showing: Option<(Note, f64)>,
+}
+
+impl Board {
+    /// An empty board.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Queue `note` behind the others, unless [`MAX_QUEUE`] are already waiting.
+    ///
+    /// Returns whether the note was queued.
+    pub fn push(&mut self, note: Note) -> bool {
+        if self.is_full() {
+            return false;
+        }
+        self.queue.push_back(note);
+        true
+    }
+
+    /// Number of notes waiting, not counting the one on screen.
+    pub fn waiting(&self) -> usize {
+        self.queue.len()
+    }
+
+    /// Whether [`MAX_QUEUE`] notes are waiting, so the next one would be
+    /// turned away.
+    pub fn is_full(&self) -> bool {
+        self.queue.len() >= MAX_QUEUE
+    }
+
+    /// Whether nothing is shown or waiting.
+    pub fn is_idle(&self) -> bool {
+        self.showing.is_none() && self.queue.is_empty()
+    }
+
+    /// The note on screen and the seconds it has been there.
+    pub fn current(&self) -> Option<(&Note, f64)> {
+        self.showing
+            .as_ref()
+            .map(|(note, seconds)| (note, *seconds))
+    }
+
+    /// Advance by `dt` seconds, taking down a note once its time is up
+    /// (`duration` unless it has its own) and putting up the next one.
+    ///
+    /// Returns the note that just went up, if any, so its effect can start.
+    pub fn update(&mut self, dt: f64, duration: Duration) -> Option<&Note> {
+        if let Some((note, seconds)) = &mut self.showing {
+            *seconds += dt;
+            if *seconds < note.duration.unwrap_or(duration).as_secs_f64() {
+                return None;
+            }
+            self.showing = None;
+        }
+        let note = self.queue.pop_front()?;
+        self.showing = Some((note, 0.0));
+        self.showing.as_ref().map(|(note, _)| note)
+    }
+}
+
+/// Accepts clients in the background and passes their notes on.
+#[derive(Debug)]
+pub struct Listener {
+    address: Address,
+    notes: Receiver<Note>,
+}
+
+impl Listener {
+    /// Start listening on `address`.
+    ///
+    /// A stale Unix socket left by a previous run is replaced, but one that
+    /// another process still listens on is an error, as is any other file in
+    /// its place, which is left alone.
+    pub fn bind(address: &Address) -> Result<Self> {
+        let (sender, notes) = mpsc::sync_channel(MAX_QUEUE);
+        let address = match address {
+            Address::Tcp(addr) => {
+                let listener = TcpListener::bind(addr)
+                    .wrap_err_with(|| format!("failed to listen on {addr}"))?;
+                let bound = Address::Tcp(listener.local_addr()?);
+                let accept = move || listener.accept().map(|(stream, _)| stream);
+                spawn(accept, sender, |stream: TcpStream| {
+                    stream.set_read_timeout(Some(IDLE_TIMEOUT))?;
+                    Ok((stream.try_clone()?, stream))
+                });
+                bound
+            }
+            #[cfg(unix)]
+            Address::Unix(path) => {
+                use std::{
+                    io::ErrorKind,
+                    os::unix::{
+                        fs::FileTypeExt,
+                        net::{UnixListener, UnixStream},
+                    },
+                };
+
+                if UnixStream::connect(path).is_ok() {
+                    bail!("{} is already in use", path.display());
+                }
+                match std::fs::symlink_metadata(path) {
+                    Ok(metadata) if metadata.file_type().is_socket() => {
+                        std::fs::remove_file(path).wrap_err_with(|| {
+                            format!("failed to remove stale socket {}", path.display())
+                        })?;
+                    }
+                    Ok(_) => bail!("{} is not a socket", path.display()),
+                    Err(err) if err.kind() == ErrorKind::NotFound => {}
+                    Err(err) => {
+                        return Err(err)
+                            .wrap_err_with(|| format!("failed to check {}", path.display()));
+                    }
+                }
+                let listener = UnixListener::bind(path)
+                    .wrap_err_with(|| format!("failed to listen on {}", path.display()))?;
+                let accept = move || listener.accept().map(|(stream, _)| stream);
+                spawn(accept, sender, |stream: UnixStream| {
+                    stream.set_read_timeout(Some(IDLE_TIMEOUT))?;
+                    Ok((stream.try_clone()?, stream))
+                });
+                Address::Unix(path.clone())
+            }
+        };
+        Ok(Self { address, notes })
+    }
+
+    /// Where clients connect, with the port filled in when bound to port 0.
+    pub fn address(&self) -> &Address {
+        &self.address
+    }
+
+    /// Notes received since the last call, oldest first.
+    pub fn notes(&self) -> impl Iterator<Item = Note> + '_ {
+        self.notes.try_iter()
+    }
+
+    /// Move received notes onto `board` while it has room, leaving the rest
+    /// waiting here; clients are told the board is full once [`MAX_QUEUE`]
+    /// notes wait in both.
+    pub fn deliver(&self, board: &mut Board) {
+        while !board.is_full() {
+            let Ok(note) = self.notes.try_recv() else {
+                return;
+            };
+            board.push(note);
+        }
+    }
+}
+
+impl Drop for Listener {
+    fn drop(&mut self) {
+        #[cfg(unix)]
+        if let Address::Unix(path) = &self.address {
+            let _ = std::fs::remove_file(path);
+        }
+    }
+}
+
+/// Accept connections in the background and serve each on its own thread,
+/// up to [`MAX_CLIENTS`] at once.
+fn spawn<S, R, W>(
+    mut accept: impl FnMut() -> io::Result<S> + Send + 'static,
+    sender: SyncSender<Note>,
+    split: fn(S) -> io::Result<(R, W)>,
+) where
+    S: Send + 'static,
+    R: Read + Send + 'static,
+    W: Write + Send + 'static,
+{
+    // Pause after a failed accept, e.g. when out of file descriptors
+    const RETRY: Duration = Duration::from_millis(100);
+
+    let clients = Arc::new(AtomicUsize::new(0));
+    thread::spawn(move || {
+        loop {
+            let stream = match accept() {
+                Ok(stream) => stream,
+                Err(_) => {
+                    thread::sleep(RETRY);
+                    continue;
+                }
+            };
+            let Ok((reader, mut writer)) = split(stream) else {
+                continue;
+            };
+            if clients.load(Ordering::Acquire) >= MAX_CLIENTS {
+                let _ = writeln!(writer, "error: too many clients");
+                continue;
+            }
+            clients.fetch_add(1, Ordering::AcqRel);
+            let (sender, clients) = (sender.clone(), Arc::clone(&clients));
+            thread::spawn(move || {
+                let served = serve(BufReader::new(reader), writer, &sender);
+                clients.fetch_sub(1, Ordering::AcqRel);
+                served
+            });
+        }
+    });
+}
+
+/// Read notes line by line from one client, answering each line.
+///
+/// Notes are refused once [`MAX_QUEUE`] are waiting to be delivered. Stops at
+/// the end of input, on a line longer than [`MAX_LINE`] bytes, when reading
+/// fails (a listener's clients time out after a minute of silence) or once
+/// the board has gone away.
+pub fn serve(
+    mut reader: impl BufRead,
+    mut writer: impl Write,
+    notes: &SyncSender<Note>,
+) -> io::Result<()> {
+    let mut line = String::new();
+    loop {
+        line.clear();
+        if (&mut reader)
+            .take(MAX_LINE as u64 + 1)
+            .read_line(&mut line)?
+            == 0
+        {
+            return Ok(());
+        }
+        if line.len() > MAX_LINE {
+            writeln!(writer, "error: lines are limited to {MAX_LINE} bytes")?;
+            return Ok(());
+        }
+        if line.trim().is_empty() {
+            continue;
+        }
+        match line.parse::<Note>() {
+            Ok(note) => match notes.try_send(note) {
+                Ok(()) => writeln!(writer, "ok")?,
+                Err(TrySendError::Full(_)) => writeln!(writer, "error: board is full")?,
+                Err(TrySendError::Disconnected(_)) => return Ok(()),
+            },
+            Err(err) => writeln!(writer, "error: {err:#}")?,
+        }
+    }
+}
@@ -0,0 +1,546 @@
+// src/cli.rs
+//! Command-line interface for the heart binary.
+
+use std::{
+    fmt::Debug, net::SocketAddr, ops::RangeInclusive, path::PathBuf, str::FromStr, time::Duration,
+};
+
+use chrono::NaiveDateTime;
+use clap::{Parser, error::ErrorKind};
+use color_eyre::{
+    Result,
+    eyre::{WrapErr, eyre},
+};
+use ratatui::style::Color;
+use ratatui_heart::{
+    board::Address,
+    config::{
+        self, BPM_RANGE, CELL_ASPECT_RANGE, CONFETTI_RANGE, CYCLE_RANGE, Config, FPS_RANGE,
+        HEART_RATE_RANGE, LAYERS_RANGE, MAX_PARTICLES_RANGE, MESSAGE_SPEED_RANGE, PULSE_RANGE,
+        SPARKLES_RANGE, SPIN_RANGE, THICKNESS_RANGE, TRANSITION_TIME_RANGE,
+    },
+    countdown,
+    fill::FillStyle,
+    headless::Format,
+    marker::Marker,
+    message::{Effect, Font, Position},
+    palette::{ColorDepth, Interpolation},
+    pulse::Easing,
+    scene::{SceneConfig, SceneKind, Transition},
+    shape::ShapeKind,
+    sync::{self, Role, Tile},
+};
+
+/// Animated rainbow Valentine heart for the terminal.
+///
+/// Settings are read from `$XDG_CONFIG_HOME/ratatui_heart/config.toml` (usually
+/// `~/.config/ratatui_heart/config.toml`) and reloaded while running. Flags given
+/// here always take precedence over the file.
+#[derive(Debug, Parser)]
+#[command(
+    version,
+    about,
+    after_help = "Press 's' to cycle shapes, 'm' to cycle markers, 'f' to toggle the fill, '3' to toggle the \
+                  solid heart, space to pause, '+'/'-' to change speed, '.' to step a frame, 'r' to reverse, '0' \
+                  to reset the view, 'n'/'p' for the next or previous scene, any other key for confetti, 'q' or ESC to quit. Click for hearts, drag to \
+                  move the heart (with the right button to turn it) and scroll to zoom."
+)]
+pub struct Cli {
+    /// Config file to load and watch instead of the default location.
+    #[arg(short, long, value_name = "PATH")]
+    pub config: Option<PathBuf>,
+
+    /// Animation frame rate in frames per second [default: 12.5].
+    #[arg(short, long, value_parser = |s: &str| parse_in_range(s, &FPS_RANGE))]
+    pub fps: Option<f64>,
+
+    /// Spacing between outline layers in world units [default: 0.05].
+    #[arg(short, long, value_parser = |s: &str| parse_in_range(s, &THICKNESS_RANGE))]
+    pub thickness: Option<f64>,
+
+    /// Number of outline layers drawn to thicken the heart [default: 4].
+    #[arg(short, long, value_parser = |s: &str| parse_in_range(s, &LAYERS_RANGE))]
+    pub layers: Option<u16>,
+
+    /// Height of a terminal cell divided by its width [default: 2].
+    #[arg(long, value_parser = |s: &str| parse_in_range(s, &CELL_ASPECT_RANGE))]
+    pub cell_aspect: Option<f64>,
+
+    /// Outline to draw [default: heart].
+    #[arg(long, value_enum)]
+    pub shape: Option<ShapeKind>,
+
+    /// Symbols the canvas draws dots with [default: braille].
+    #[arg(long, value_enum)]
+    pub marker: Option<Marker>,
+
+    /// Fill the inside of the shape instead of drawing only its outline.
+    #[arg(long)]
+    pub filled: bool,
+
+    /// Draw a shaded, turning 3D heart instead of the flat shape.
+    #[arg(long)]
+    pub solid: bool,
+
+    /// Turns per second of the solid heart, negative for the other way [default: 0.25].
+    #[arg(long, value_parser = |s: &str| parse_in_range(s, &SPIN_RANGE), allow_negative_numbers = true)]
+    pub spin: Option<f64>,
+
+    /// How the inside is colored when filled [default: solid].
+    #[arg(long, value_enum)]
+    pub fill_style: Option<FillStyle>,
+
+    /// Solid fill color, e.g. `red` or `#ff0066` [default: outline color].
+    #[arg(long, value_name = "COLOR", value_parser = parse_color)]
+    pub fill_color: Option<Color>,
+
+    /// Heart rate of the pulse in beats per minute [default: 72].
+    #[arg(long, value_parser = |s: &str| parse_in_range(s, &BPM_RANGE))]
+    pub bpm: Option<f64>,
+
+    /// Extra scale at the peak of each beat, 0 to keep still [default: 0.08].
+    #[arg(long, value_parser = |s: &str| parse_in_range(s, &PULSE_RANGE))]
+    pub pulse: Option<f64>,
+
+    /// Curve for the rise and fall of each beat [default: sine].
+    #[arg(long, value_enum)]
+    pub easing: Option<Easing>,
+
+    /// Color palette to cycle through: rainbow, rose, sunset, pastel, pink or a
+    /// name from `[palettes]` in the config file [default: rainbow].
+    #[arg(short, long, value_name = "NAME")]
+    pub palette: Option<String>,
+
+    /// Color space used to blend between palette colors [default: oklch].
+    #[arg(long, value_enum)]
+    pub interpolation: Option<Interpolation>,
+
+    /// Seconds for one trip through the whole palette [default: 2].
+    #[arg(long, value_parser = |s: &str| parse_in_range(s, &CYCLE_RANGE))]
+    pub cycle: Option<f64>,
+
+    /// Palette trips once around the outline, 0 for a single color [default: 0].
+    #[arg(long, value_parser = parse_finite, allow_negative_numbers = true)]
+    pub spread: Option<f64>,
+
+    /// Palette offset between neighbouring outline layers [default: 0].
+    #[arg(long, value_parser = parse_finite, allow_negative_numbers = true)]
+    pub layer_shift: Option<f64>,
+
+    /// Colors the terminal can show [default: auto, from COLORTERM/TERM].
+    #[arg(long, value_enum)]
+    pub color_depth: Option<ColorDepth>,
+
+    /// Mini-hearts rising from the bottom edge per second, 0 for none [default: 1].
+    #[arg(long, value_parser = |s: &str| parse_in_range(s, &HEART_RATE_RANGE))]
+    pub heart_rate: Option<f64>,
+
+    /// Sparkles thrown off the outline on each beat [default: 8].
+    #[arg(long, value_parser = |s: &str| parse_in_range(s, &SPARKLES_RANGE))]
+    pub sparkles: Option<u32>,
+
+    /// Confetti pieces thrown on each key press [default: 60].
+    #[arg(long, value_parser = |s: &str| parse_in_range(s, &CONFETTI_RANGE))]
+    pub confetti: Option<u32>,
+
+    /// Most particles alive at once [default: 300].
+    #[arg(long, value_parser = |s: &str| parse_in_range(s, &MAX_PARTICLES_RANGE))]
+    pub max_particles: Option<usize>,
+
+    /// Quit automatically after this long (e.g. `90`, `30s`, `5m`, `1h`).
+    #[arg(short, long, value_parser = config::parse_duration)]
+    pub duration: Option<Duration>,
+
+    /// Message shown with the heart.
+    #[arg(short, long)]
+    pub message: Option<String>,
+
+    /// Where the message goes relative to the heart [default: below].
+    #[arg(long, value_enum)]
+    pub message_position: Option<Position>,
+
+    /// Lettering of the message [default: plain].
+    #[arg(long, value_enum)]
+    pub message_font: Option<Font>,
+
+    /// How the message appears over time [default: none].
+    #[arg(long, value_enum)]
+    pub message_effect: Option<Effect>,
+
+    /// Characters per second for the typewriter and marquee effects [default: 10].
+    #[arg(long, value_parser = |s: &str| parse_in_range(s, &MESSAGE_SPEED_RANGE))]
+    pub message_speed: Option<f64>,
+
+    /// Seed for the starting animation phase.
+    #[arg(short, long)]
+    pub seed: Option<u64>,
+
+    /// Count down to `--countdown-target` beside the heart, then celebrate.
+    #[arg(long)]
+    pub countdown: bool,
+
+    /// Local date and time to count down to, e.g. 2027-02-14 or "2027-02-14 18:30"
+    /// [default: the next 14 February].
+    #[arg(long, value_name = "DATE", value_parser = parse_countdown_target)]
+    pub countdown_target: Option<NaiveDateTime>,
+
+    /// Message shown when the countdown reaches zero [default: Happy Valentine's Day!].
+    #[arg(long, value_name = "TEXT")]
+    pub countdown_message: Option<String>,
+
+    /// Show these scenes in turn instead of the heart alone, e.g. heart,message,shower,solid.
+    #[arg(long, value_enum, value_delimiter = ',', num_args = 1..)]
+    pub scenes: Option<Vec<SceneKind>>,
+
+    /// How long each scene stays, e.g. 30s or 2m [default: 10s].
+    #[arg(long, value_parser = config::parse_duration)]
+    pub scene_duration: Option<Duration>,
+
+    /// How one scene replaces the next [default: crossfade].
+    #[arg(long, value_enum)]
+    pub transition: Option<Transition>,
+
+    /// Seconds a transition between scenes takes [default: 1].
+    #[arg(long, value_parser = |s: &str| parse_in_range(s, &TRANSITION_TIME_RANGE))]
+    pub transition_time: Option<f64>,
+
+    /// Leave the mouse to the terminal, e.g. for selecting text.
+    #[arg(long)]
+    pub no_mouse: bool,
+
+    /// Run as a screensaver: the heart wanders about and any key or mouse input quits.
+    #[arg(long)]
+    pub screensaver: bool,
+
+    /// Turn into a screensaver after this long without input, e.g. 5m.
+    #[arg(long, value_name = "DURATION", value_parser = config::parse_duration)]
+    pub screensaver_after: Option<Duration>,
+
+    /// Receive message board notes on a localhost port or Unix socket, e.g. 7777 or
+    /// /tmp/heart.sock.
+    #[arg(long, value_name = "ADDRESS", value_parser = parse_listen)]
+    pub listen: Option<Address>,
+
+    /// How long a message board note stays unless it says otherwise [default: 8s].
+    #[arg(long, value_name = "DURATION", value_parser = config::parse_duration)]
+    pub note_duration: Option<Duration>,
+
+    /// Lead other hearts or follow one, to animate in phase.
+    #[arg(long, value_enum, value_name = "ROLE")]
+    pub sync: Option<Role>,
+
+    /// Localhost UDP port or address the leader listens on [default: 127.0.0.1:7878].
+    #[arg(long, value_name = "ADDRESS", value_parser = parse_sync_address)]
+    pub sync_address: Option<SocketAddr>,
+
+    /// Show part of one heart spread over a grid of terminals, e.g. 2x1@0,0 for
+    /// the left half.
+    #[arg(long, value_name = "GRID@COLUMN,ROW", value_parser = parse_tile)]
+    pub tile: Option<Tile>,
+
+    /// Print frames to stdout instead of running interactively.
+    #[arg(long, value_enum, value_name = "FORMAT", group = "output")]
+    pub headless: Option<Format>,
+
+    /// Write the animation to an animated GIF instead of running interactively.
+    #[arg(long, value_name = "PATH", group = "output")]
+    pub export_gif: Option<PathBuf>,
+
+    /// Write the animation to an asciinema v2 recording.
+    #[arg(long, value_name = "PATH", group = "output")]
+    pub export_cast: Option<PathBuf>,
+
+    /// Write the animation to a self-animating SVG.
+    #[arg(long, value_name = "PATH", group = "output")]
+    pub export_svg: Option<PathBuf>,
+
+    /// Size of headless and exported frames as WIDTHxHEIGHT cells.
+    #[arg(long, value_name = "WxH", default_value = "80x24", value_parser = parse_size)]
+    pub size: (u16, u16),
+
+    /// Number of consecutive frames to print or export, up to 100000 [default:
+    /// 1 for --headless, one palette cycle for exports].
+    #[arg(long, value_parser = clap::value_parser!(u64).range(1..=100_000))]
+    pub frames: Option<u64>,
+}
+
+/// Where frames rendered without a terminal go.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Target {
+    /// Printed to stdout in the given format.
+    Stdout(Format),
+    /// Encoded as an animated GIF at this path.
+    Gif(PathBuf),
+    /// Recorded as an asciinema cast at this path.
+    Cast(PathBuf),
+    /// Laid out as an animated SVG at this path.
+    Svg(PathBuf),
+}
+
+/// Settings of a non-interactive run.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Headless {
+    /// Where the frames go.
+    pub target: Target,
+    /// Frame width in cells.
+    pub width: u16,
+    /// Frame height in cells.
+    pub height: u16,
+    /// Number of consecutive frames, if given.
+    pub frames: Option<u64>,
+}
+
+impl Cli {
+    /// Parse the process arguments, reporting invalid input through `color_eyre`.
+    ///
+    /// `--help` and `--version` print their output and exit as usual.
+    pub fn load() -> Result<Self> {
+        match Cli::try_parse() {
+            Ok(cli) => Ok(cli),
+            Err(err)
+                if matches!(
+                    err.kind(),
+                    ErrorKind::DisplayHelp | ErrorKind::DisplayVersion
+                ) =>
+            {
+                err.exit()
+            }
+            Err(err) => Err(eyre!(err.render().to_string().trim_end().to_owned()))
+                .wrap_err("invalid command-line arguments"),
+        }
+    }
+
+    /// Whether frames are written to a file rather than shown in this terminal.
+    pub fn exports(&self) -> bool {
+        self.export_gif.is_some() || self.export_cast.is_some() || self.export_svg.is_some()
+    }
+
+    /// Settings of a non-interactive run, if `--headless` or an export was requested.
+    pub fn headless(&self) -> Option<Headless> {
+        let target = if let Some(path) = &self.export_gif {
+            Target::Gif(path.clone())
+        } else if let Some(path) = &self.export_cast {
+            Target::Cast(path.clone())
+        } else if let Some(path) = &self.export_svg {
+            Target::Svg(path.clone())
+        } else {
+            Target::Stdout(self.headless?)
+        };
+        Some(Headless {
+            target,
+            width: self.size.0,
+            height: self.size.1,
+            frames: self.frames,
+        })
+    }
+
+    /// Path of the config file to watch, if any.
+    pub fn config_path(&self) -> Option<PathBuf> {
+        self.config.clone().or_else(config::default_path)
+    }
+
+    /// Override the settings in `config` with the flags given on the command line.
+    pub fn apply(&self, config: &mut Config) {
+        if let Some(fps) = self.fps {
+            config.fps = fps;
+        }
+        if let Some(thickness) = self.thickness {
+            config.thickness = thickness;
+        }
+        if let Some(layers) = self.layers {
+            config.layers = layers;
+        }
+        if let Some(cell_aspect) = self.cell_aspect {
+            config.cell_aspect = cell_aspect;
+        }
+        if let Some(shape) = self.shape {
+            config.shape = shape;
+        }
+        if let Some(marker) = self.marker {
+            config.marker = marker;
+        }
+        if self.filled {
+            config.filled = true;
+        }
+        if self.solid {
+            config.solid = true;
+        }
+        if let Some(spin) = self.spin {
+            config.spin = spin;
+        }
+        if let Some(fill_style) = self.fill_style {
+            config.fill_style = fill_style;
+        }
+        if self.fill_color.is_some() {
+            config.fill_color = self.fill_color;
+        }
+        if let Some(bpm) = self.bpm {
+            config.bpm = bpm;
+        }
+        if let Some(pulse) = self.pulse {
+            config.pulse = pulse;
+        }
+        if let Some(easing) = self.easing {
+            config.easing = easing;
+        }
+        if let Some(palette) = &self.palette {
+            config.palette.clone_from(palette);
+            config.colors.clear();
+        }
+        if let Some(interpolation) = self.interpolation {
+            config.interpolation = interpolation;
+        }
+        if let Some(cycle) = self.cycle {
+            config.cycle = cycle;
+        }
+        if let Some(spread) = self.spread {
+            config.spread = spread;
+        }
+        if let Some(layer_shift) = self.layer_shift {
+            config.layer_shift = layer_shift;
+        }
+        if let Some(color_depth) = self.color_depth {
+            config.color_depth = color_depth;
+        }
+        if let Some(heart_rate) = self.heart_rate {
+            config.heart_rate = heart_rate;
+        }
+        if let Some(sparkles) = self.sparkles {
+            config.sparkles = sparkles;
+        }
+        if let Some(confetti) = self.confetti {
+            config.confetti = confetti;
+        }
+        if let Some(max_particles) = self.max_particles {
+            config.max_particles = max_particles;
+        }
+        if self.duration.is_some() {
+            config.duration = self.duration;
+        }
+        if self.message.is_some() {
+            config.message.clone_from(&self.message);
+        }
+        if let Some(position) = self.message_position {
+            config.message_position = position;
+        }
+        if let Some(font) = self.message_font {
+            config.message_font = font;
+        }
+        if let Some(effect) = self.message_effect {
+            config.message_effect = effect;
+        }
+        if let Some(speed) = self.message_speed {
+            config.message_speed = speed;
+        }
+        if self.seed.is_some() {
+            config.seed = self.seed;
+        }
+        if self.countdown {
+            config.countdown = true;
+        }
+        if self.countdown_target.is_some() {
+            config.countdown_target = self.countdown_target;
+        }
+        if let Some(message) = &self.countdown_message {
+            config.countdown_message.clone_from(message);
+        }
+        if let Some(kinds) = &self.scenes {
+            config.scenes = kinds
+                .iter()
+                .map(|&kind| SceneConfig {
+                    kind,
+                    ..SceneConfig::default()
+                })
+                .collect();
+        }
+        if let Some(scene_duration) = self.scene_duration {
+            config.scene_duration = scene_duration;
+        }
+        if let Some(transition) = self.transition {
+            config.transition = transition;
+        }
+        if let Some(transition_time) = self.transition_time {
+            config.transition_time = transition_time;
+        }
+        if self.no_mouse {
+            config.mouse = false;
+        }
+        if self.screensaver {
+            config.screensaver = true;
+        }
+        if self.screensaver_after.is_some() {
+            config.screensaver_after = self.screensaver_after;
+        }
+        if self.listen.is_some() {
+            config.listen.clone_from(&self.listen);
+        }
+        if let Some(note_duration) = self.note_duration {
+            config.note_duration = note_duration;
+        }
+        if self.sync.is_some() {
+            config.sync = self.sync;
+        }
+        if let Some(address) = self.sync_address {
+            config.sync_address = address;
+        }
+        if self.tile.is_some() {
+            config.tile = self.tile;
+        }
+    }
+}
+
+/// Parse a number and check that it lies within `range`.
+fn parse_in_range<T>(s: &str, range: &RangeInclusive<T>) -> Result<T, String>
+where
+    T: FromStr + PartialOrd + Debug,
+{
+    let value: T = s
+        .trim()
+        .parse()
+        .map_err(|_| format!("`{s}` is not a number"))?;
+    if !range.contains(&value) {
+        return Err(format!(
+            "{value:?} is outside the supported range {range:?}"
+        ));
+    }
+    Ok(value)
+}
+
+fn parse_finite(s: &str) -> Result<f64, String> {
+    match s.parse::<f64>() {
+        Ok(value) if value.is_finite() => Ok(value),
+        _ => Err(format!("`{s}` is not a finite number")),
+    }
+}
+
+fn parse_countdown_target(s: &str) -> Result<NaiveDateTime, String> {
+    countdown::parse_target(s).map_err(|e| e.to_string())
+}
+
+fn parse_listen(s: &str) -> Result<Address, String> {
+    s.parse().map_err(|e: color_eyre::Report| format!("{e:#}"))
+}
+
+fn parse_sync_address(s: &str) -> Result<SocketAddr, String> {
+    sync::parse_address(s).map_err(|e| format!("{e:#}"))
+}
+
+fn parse_tile(s: &str) -> Result<Tile, String> {
+    s.parse().map_err(|e: color_eyre::Report| e.to_string())
+}
+
+fn parse_color(s: &str) -> Result<Color, String> {
+    s.parse()
+        .map_err(|_| format!("`{s}` is not a color name, 0-255 index or #rrggbb value"))
+}
+
+fn parse_size(s: &str) -> Result<(u16, u16), String> {
+    let invalid = || format!("`{s}` is not a size like 80x24");
+    let (width, height) = s.split_once(['x', 'X']).ok_or_else(invalid)?;
+    let width: u16 = width.trim().parse().map_err(|_| invalid())?;
+    let height: u16 = height.trim().parse().map_err(|_| invalid())?;
+    if width == 0 || height == 0 {
+        return Err(format!("{width}x{height} has no area"));
+    }
+    Ok((width, height))
+}
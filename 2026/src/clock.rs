@@ -0,0 +1,141 @@
+// src/clock.rs
+//! Animation time that follows the wall clock but can be paused, sped up,
+//! slowed down, stepped and run backwards.
+//!
+//! Every time-based effect (palette cycle, heartbeat, message effects,
+//! particles) reads [`Clock::seconds`], so the animation speed no longer
+//! depends on how often frames are drawn.
+
+use std::{
+    ops::RangeInclusive,
+    time::{Duration, Instant},
+};
+
+/// Supported playback speed multipliers.
+pub const SPEED_RANGE: RangeInclusive<f64> = 0.125..=8.0;
+
+/// Animation time driven by real elapsed time.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Clock {
+    /// Animation seconds shown right now.
+    seconds: f64,
+    /// Playback speed multiplier, always positive.
+    speed: f64,
+    paused: bool,
+    reversed: bool,
+    /// Wall-clock time of the last update.
+    last: Instant,
+}
+
+impl Clock {
+    /// A running clock at normal speed starting from `seconds`.
+    pub fn new(seconds: f64) -> Self {
+        Self {
+            seconds,
+            speed: 1.0,
+            paused: false,
+            reversed: false,
+            last: Instant::now(),
+        }
+    }
+
+    /// Animation seconds since the start.
+    pub fn seconds(&self) -> f64 {
+        self.seconds
+    }
+
+    /// Signed playback rate: negative when reversed, zero when paused.
+    pub fn rate(&self) -> f64 {
+        match (self.paused, self.reversed) {
+            (true, _) => 0.0,
+            (false, false) => self.speed,
+            (false, true) => -self.speed,
+        }
+    }
+
+    /// Whether the clock is stopped.
+    pub fn is_paused(&self) -> bool {
+        self.paused
+    }
+
+    /// Advance by the real time since the last update, returning how many
+    /// animation seconds passed (negative when reversed).
+    pub fn update(&mut self) -> f64 {
+        self.update_at(Instant::now())
+    }
+
+    /// Advance to the wall-clock time `now`, like [`Clock::update`].
+    pub fn update_at(&mut self, now: Instant) -> f64 {
+        let real = now.saturating_duration_since(self.last);
+        self.last = now;
+        let delta = real.as_secs_f64() * self.rate();
+        self.seconds += delta;
+        delta
+    }
+
+    /// Jump to animation time `seconds`, returning how far the clock moved.
+    pub fn seek(&mut self, seconds: f64) -> f64 {
+        let delta = seconds - self.seconds;
+        self.seconds = seconds;
+        delta
+    }
+
+    /// Take on a signed playback `rate` as reported by [`Clock::rate`],
+    /// e.g. another clock's, with the speed kept within [`SPEED_RANGE`].
+    pub fn lock(&mut self, rate: f64) {
+        self.paused = rate == 0.0;
+        if !self.paused {
+            self.reversed = rate < 0.0;
+            self.speed = rate.abs().clamp(*SPEED_RANGE.start(), *SPEED_RANGE.end());
+        }
+    }
+
+    /// Pause a running clock or resume a paused one.
+    pub fn toggle_pause(&mut self) {
+        self.paused = !self.paused;
+    }
+
+    /// Double the playback speed, up to the top of [`SPEED_RANGE`].
+    pub fn faster(&mut self) {
+        self.speed = (self.speed * 2.0).min(*SPEED_RANGE.end());
+    }
+
+    /// Halve the playback speed, down to the bottom of [`SPEED_RANGE`].
+    pub fn slower(&mut self) {
+        self.speed = (self.speed / 2.0).max(*SPEED_RANGE.start());
+    }
+
+    /// Swap between forward and backward playback.
+    pub fn reverse(&mut self) {
+        self.reversed = !self.reversed;
+    }
+
+    /// Pause and move one `frame` of animation time in the current direction,
+    /// returning the signed step.
+    pub fn step(&mut self, frame: Duration) -> f64 {
+        self.paused = true;
+        let delta = if self.reversed {
+            -frame.as_secs_f64()
+        } else {
+            frame.as_secs_f64()
+        };
+        self.seconds += delta;
+        delta
+    }
+
+    /// Short description of any non-default playback state, e.g. `paused` or
+    /// `reverse 2x`.
+    pub fn status(&self) -> Option<String> {
+        let mut parts = Vec::new();
+        if self.paused {
+            parts.push(String::from("paused"));
+        }
+        if self.reversed {
+            parts.push(String::from("reverse"));
+        }
+        if self.speed != 1.0 {
+            parts.push(format!("{}x", self.speed));
+        }
+        (!parts.is_empty()).then(|| parts.join(" "))
+    }
+}
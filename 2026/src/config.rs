@@ -0,0 +1,612 @@
+// src/config.rs
+//! Runtime settings loaded from `config.toml` and reloaded while running.
+
+use std::{
+    collections::BTreeMap,
+    env, fs,
+    net::SocketAddr,
+    ops::RangeInclusive,
+    path::{Path, PathBuf},
+    time::{Duration, Instant, SystemTime},
+};
+
+use chrono::NaiveDateTime;
+use clap::ValueEnum;
+use color_eyre::{
+    Result,
+    eyre::{WrapErr, bail, ensure, eyre},
+};
+use ratatui::style::Color;
+use serde::{Deserialize, Deserializer};
+
+use crate::{
+    board::Address,
+    countdown,
+    fill::FillStyle,
+    marker::Marker,
+    message::{Effect, Font, Position},
+    palette::{self, ColorDepth, Gradient, Interpolation, Palette},
+    pulse::{Easing, Heartbeat},
+    scene::{SceneConfig, SceneKind, Transition},
+    shape::{
+        BrokenHeart, Cardioid, Heart, ImplicitHeart, Point, Polyline, Rose, Shape, ShapeKind, Star,
+        TwinHearts,
+    },
+    sync::{self, Role, Tile},
+};
+
+/// Supported animation frame rates.
+pub const FPS_RANGE: RangeInclusive<f64> = 0.5..=120.0;
+/// Supported spacing between outline layers.
+pub const THICKNESS_RANGE: RangeInclusive<f64> = 0.0..=0.5;
+/// Supported number of outline layers.
+pub const LAYERS_RANGE: RangeInclusive<u16> = 1..=32;
+/// Supported number of samples along the outline.
+pub const STEPS_RANGE: RangeInclusive<usize> = 16..=20_000;
+/// Supported heart rates in beats per minute.
+pub const BPM_RANGE: RangeInclusive<f64> = 20.0..=240.0;
+/// Supported pulse amplitudes.
+pub const PULSE_RANGE: RangeInclusive<f64> = 0.0..=0.5;
+/// Supported palette cycle lengths in seconds.
+pub const CYCLE_RANGE: RangeInclusive<f64> = 0.1..=600.0;
+/// Supported message effect speeds in characters per second.
+pub const MESSAGE_SPEED_RANGE: RangeInclusive<f64> = 0.5..=200.0;
+/// Supported rising hearts per second.
+pub const HEART_RATE_RANGE: RangeInclusive<f64> = 0.0..=50.0;
+/// Supported sparkles per beat.
+pub const SPARKLES_RANGE: RangeInclusive<u32> = 0..=200;
+/// Supported confetti pieces per burst.
+pub const CONFETTI_RANGE: RangeInclusive<u32> = 0..=1000;
+/// Supported caps on live particles.
+pub const MAX_PARTICLES_RANGE: RangeInclusive<usize> = 0..=5000;
+/// Supported terminal cell height-to-width ratios.
+pub const CELL_ASPECT_RANGE: RangeInclusive<f64> = 0.5..=4.0;
+/// Supported turns per second of the solid heart; negative turns the other way.
+pub const SPIN_RANGE: RangeInclusive<f64> = -5.0..=5.0;
+/// Supported seconds of a transition between scenes.
+pub const TRANSITION_TIME_RANGE: RangeInclusive<f64> = 0.0..=10.0;
+/// Supported number of rose curve petals.
+pub const PETALS_RANGE: RangeInclusive<u32> = 1..=24;
+
+/// Every tunable setting of the animation.
+///
+/// Missing keys in the config file fall back to the defaults below.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields, rename_all = "kebab-case")]
+pub struct Config {
+    /// Animation frame rate in frames per second.
+    pub fps: f64,
+    /// Spacing between outline layers in world units.
+    pub thickness: f64,
+    /// Number of outline layers drawn to thicken the heart.
+    pub layers: u16,
+    /// Number of samples along the outline of each layer; adapts to the canvas
+    /// resolution when unset.
+    pub steps: Option<usize>,
+    /// Half-width of the square world that always fits on the canvas.
+    pub bounds: f64,
+    /// Height of a terminal cell divided by its width, used to keep the shape's
+    /// proportions.
+    pub cell_aspect: f64,
+    /// Outline to draw.
+    pub shape: ShapeKind,
+    /// Symbols the canvas draws dots with.
+    pub marker: Marker,
+    /// Draw a shaded, turning 3D heart instead of the flat shape.
+    pub solid: bool,
+    /// Turns per second of the solid heart.
+    pub spin: f64,
+    /// Number of petals of the `rose` shape.
+    pub petals: u32,
+    /// Vertices of the `polyline` shape in world coordinates.
+    pub points: Vec<Point>,
+    /// Heart rate of the pulse in beats per minute.
+    pub bpm: f64,
+    /// Extra scale at the peak of each beat; `0` keeps the shape still.
+    pub pulse: f64,
+    /// Curve for the rise and fall of each beat.
+    pub easing: Easing,
+    /// Built-in or user-defined palette to cycle through.
+    pub palette: String,
+    /// User-defined palettes by name, e.g. `[palettes] ocean = ["#003366", "#66ccff"]`.
+    pub palettes: BTreeMap<String, Vec<Color>>,
+    /// Explicit colors to cycle through instead of the named palette.
+    pub colors: Vec<Color>,
+    /// Color space used to blend between palette colors.
+    pub interpolation: Interpolation,
+    /// Seconds for one trip through the whole palette.
+    pub cycle: f64,
+    /// How many trips through the palette fit once around the outline; `0` colors
+    /// the whole outline alike.
+    pub spread: f64,
+    /// Palette offset between neighbouring outline layers.
+    pub layer_shift: f64,
+    /// Colors the terminal can show; `auto` is detected from `COLORTERM`/`TERM`
+    /// by [`ColorDepth::resolve`] and otherwise draws colors unchanged.
+    pub color_depth: ColorDepth,
+    /// Fill the inside of the shape instead of drawing only its outline.
+    pub filled: bool,
+    /// How the inside is colored when `filled` is on.
+    pub fill_style: FillStyle,
+    /// Solid fill color; follows the outline color when unset.
+    pub fill_color: Option<Color>,
+    /// Top and bottom colors of the gradient fill.
+    pub gradient: [Color; 2],
+    /// Mini-hearts rising from the bottom edge per second.
+    pub heart_rate: f64,
+    /// Sparkles thrown off the outline on each beat.
+    pub sparkles: u32,
+    /// Confetti pieces per burst, thrown on key presses.
+    pub confetti: u32,
+    /// Most particles alive at once; new ones are dropped beyond this.
+    pub max_particles: usize,
+    /// Quit automatically after this long.
+    #[serde(deserialize_with = "deserialize_duration")]
+    pub duration: Option<Duration>,
+    /// Message shown with the heart.
+    pub message: Option<String>,
+    /// Where the message goes relative to the heart.
+    pub message_position: Position,
+    /// Lettering of the message.
+    pub message_font: Font,
+    /// How the message appears over time.
+    pub message_effect: Effect,
+    /// Characters per second for the typewriter and marquee effects.
+    pub message_speed: f64,
+    /// Count down to `countdown-target` beside the heart instead of showing it alone.
+    pub countdown: bool,
+    /// Local date and time counted down to; the next 14 February when unset.
+    #[serde(deserialize_with = "deserialize_target")]
+    pub countdown_target: Option<NaiveDateTime>,
+    /// Message celebrating the end of the countdown.
+    pub countdown_message: String,
+    /// Seed for the starting animation phase.
+    pub seed: Option<u64>,
+    /// Capture the mouse to spawn hearts and move, turn and zoom the heart.
+    pub mouse: bool,
+    /// Start as a screensaver: the heart wanders about and any input quits.
+    pub screensaver: bool,
+    /// Turn into a screensaver after this long without input.
+    #[serde(deserialize_with = "deserialize_duration")]
+    pub screensaver_after: Option<Duration>,
+    /// Socket to receive message board notes on; read at startup.
+    pub listen: Option<Address>,
+    /// How long a note stays unless it says otherwise.
+    #[serde(deserialize_with = "deserialize_required_duration")]
+    pub note_duration: Duration,
+    /// Lead or follow other hearts to stay in phase; read at startup.
+    pub sync: Option<Role>,
+    /// Loopback UDP address the leader listens on; read at startup.
+    #[serde(deserialize_with = "sync::deserialize_address")]
+    pub sync_address: SocketAddr,
+    /// This terminal's part of one large heart spread over several terminals.
+    pub tile: Option<Tile>,
+    /// Scenes shown in turn; the heart alone when empty.
+    pub scenes: Vec<SceneConfig>,
+    /// How long a scene stays unless it says otherwise.
+    #[serde(deserialize_with = "deserialize_required_duration")]
+    pub scene_duration: Duration,
+    /// How a scene replaces the one before unless it says otherwise.
+    pub transition: Transition,
+    /// Seconds a transition between scenes takes.
+    pub transition_time: f64,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            fps: 12.5,
+            thickness: 0.05,
+            layers: 4,
+            steps: None,
+            bounds: 2.0,
+            cell_aspect: 2.0,
+            shape: ShapeKind::default(),
+            marker: Marker::default(),
+            solid: false,
+            spin: 0.25,
+            petals: 5,
+            points: Vec::new(),
+            bpm: 72.0,
+            pulse: 0.08,
+            easing: Easing::default(),
+            palette: String::from("rainbow"),
+            palettes: BTreeMap::new(),
+            colors: Vec::new(),
+            interpolation: Interpolation::default(),
+            cycle: 2.0,
+            spread: 0.0,
+            layer_shift: 0.0,
+            color_depth: ColorDepth::default(),
+            filled: false,
+            fill_style: FillStyle::default(),
+            fill_color: None,
+            gradient: [Color::Rgb(255, 102, 153), Color::Rgb(139, 0, 48)],
+            heart_rate: 1.0,
+            sparkles: 8,
+            confetti: 60,
+            max_particles: 300,
+            duration: None,
+            message: None,
+            message_position: Position::default(),
+            message_font: Font::default(),
+            message_effect: Effect::default(),
+            message_speed: 10.0,
+            countdown: false,
+            countdown_target: None,
+            countdown_message: String::from("Happy Valentine's Day!"),
+            seed: None,
+            mouse: true,
+            screensaver: false,
+            screensaver_after: None,
+            listen: None,
+            note_duration: Duration::from_secs(8),
+            sync: None,
+            sync_address: sync::DEFAULT_ADDRESS,
+            tile: None,
+            scenes: Vec::new(),
+            scene_duration: Duration::from_secs(10),
+            transition: Transition::default(),
+            transition_time: 1.0,
+        }
+    }
+}
+
+impl Config {
+    /// Parse and validate a config from TOML source.
+    pub fn from_toml(source: &str) -> Result<Self> {
+        let config: Config = toml::from_str(source)?;
+        config.validate()?;
+        Ok(config)
+    }
+
+    /// Read and validate the config file at `path`.
+    pub fn load(path: &Path) -> Result<Self> {
+        let source = fs::read_to_string(path)
+            .wrap_err_with(|| format!("failed to read {}", path.display()))?;
+        Self::from_toml(&source).wrap_err_with(|| format!("invalid config in {}", path.display()))
+    }
+
+    /// Check that every value is within its supported range.
+    pub fn validate(&self) -> Result<()> {
+        ensure!(
+            FPS_RANGE.contains(&self.fps),
+            "fps {} is outside the supported range {FPS_RANGE:?}",
+            self.fps
+        );
+        ensure!(
+            THICKNESS_RANGE.contains(&self.thickness),
+            "thickness {} is outside the supported range {THICKNESS_RANGE:?}",
+            self.thickness
+        );
+        ensure!(
+            LAYERS_RANGE.contains(&self.layers),
+            "layers {} is outside the supported range {LAYERS_RANGE:?}",
+            self.layers
+        );
+        if let Some(steps) = self.steps {
+            ensure!(
+                STEPS_RANGE.contains(&steps),
+                "steps {steps} is outside the supported range {STEPS_RANGE:?}"
+            );
+        }
+        ensure!(
+            self.bounds.is_finite() && self.bounds > 0.0,
+            "bounds must be a positive number, got {}",
+            self.bounds
+        );
+        ensure!(
+            CELL_ASPECT_RANGE.contains(&self.cell_aspect),
+            "cell-aspect {} is outside the supported range {CELL_ASPECT_RANGE:?}",
+            self.cell_aspect
+        );
+        ensure!(
+            SPIN_RANGE.contains(&self.spin),
+            "spin {} is outside the supported range {SPIN_RANGE:?}",
+            self.spin
+        );
+        ensure!(
+            BPM_RANGE.contains(&self.bpm),
+            "bpm {} is outside the supported range {BPM_RANGE:?}",
+            self.bpm
+        );
+        ensure!(
+            PULSE_RANGE.contains(&self.pulse),
+            "pulse {} is outside the supported range {PULSE_RANGE:?}",
+            self.pulse
+        );
+        ensure!(
+            CYCLE_RANGE.contains(&self.cycle),
+            "cycle {} is outside the supported range {CYCLE_RANGE:?}",
+            self.cycle
+        );
+        ensure!(
+            self.spread.is_finite() && self.layer_shift.is_finite(),
+            "spread and layer-shift must be finite numbers"
+        );
+        ensure!(
+            self.palettes.contains_key(&self.palette)
+                || Palette::from_str(&self.palette, true).is_ok(),
+            "unknown palette \"{}\", expected one of {} or a name from [palettes]",
+            self.palette,
+            Palette::value_variants()
+                .iter()
+                .filter_map(|palette| palette.to_possible_value())
+                .map(|value| value.get_name().to_owned())
+                .collect::<Vec<_>>()
+                .join(", ")
+        );
+        if let Some((name, _)) = self.palettes.iter().find(|(_, colors)| colors.is_empty()) {
+            bail!("palette \"{name}\" has no colors");
+        }
+        ensure!(
+            HEART_RATE_RANGE.contains(&self.heart_rate),
+            "heart-rate {} is outside the supported range {HEART_RATE_RANGE:?}",
+            self.heart_rate
+        );
+        ensure!(
+            SPARKLES_RANGE.contains(&self.sparkles),
+            "sparkles {} is outside the supported range {SPARKLES_RANGE:?}",
+            self.sparkles
+        );
+        ensure!(
+            CONFETTI_RANGE.contains(&self.confetti),
+            "confetti {} is outside the supported range {CONFETTI_RANGE:?}",
+            self.confetti
+        );
+        ensure!(
+            MAX_PARTICLES_RANGE.contains(&self.max_particles),
+            "max-particles {} is outside the supported range {MAX_PARTICLES_RANGE:?}",
+            self.max_particles
+        );
+        ensure!(
+            MESSAGE_SPEED_RANGE.contains(&self.message_speed),
+            "message-speed {} is outside the supported range {MESSAGE_SPEED_RANGE:?}",
+            self.message_speed
+        );
+        ensure!(
+            self.screensaver_after.is_none_or(|d| !d.is_zero()),
+            "screensaver-after must be longer than zero"
+        );
+        ensure!(
+            !self.note_duration.is_zero(),
+            "note-duration must be longer than zero"
+        );
+        ensure!(
+            !self.scene_duration.is_zero(),
+            "scene-duration must be longer than zero"
+        );
+        ensure!(
+            self.scenes
+                .iter()
+                .all(|scene| scene.duration.is_none_or(|d| !d.is_zero())),
+            "every scene duration must be longer than zero"
+        );
+        ensure!(
+            TRANSITION_TIME_RANGE.contains(&self.transition_time),
+            "transition-time {} is outside the supported range {TRANSITION_TIME_RANGE:?}",
+            self.transition_time
+        );
+        ensure!(
+            PETALS_RANGE.contains(&self.petals),
+            "petals {} is outside the supported range {PETALS_RANGE:?}",
+            self.petals
+        );
+        ensure!(
+            self.shape != ShapeKind::Polyline || self.points.len() >= 3,
+            "shape \"polyline\" needs at least 3 `points`, got {}",
+            self.points.len()
+        );
+        ensure!(
+            self.points
+                .iter()
+                .all(|(x, y)| x.is_finite() && y.is_finite()),
+            "points must be finite numbers"
+        );
+        Ok(())
+    }
+
+    /// Build the selected shape.
+    pub fn shape(&self) -> Box<dyn Shape> {
+        match self.shape {
+            ShapeKind::Heart => Box::new(Heart),
+            ShapeKind::Cardioid => Box::new(Cardioid),
+            ShapeKind::Implicit => Box::new(ImplicitHeart),
+            ShapeKind::Broken => Box::new(BrokenHeart),
+            ShapeKind::Twin => Box::new(TwinHearts),
+            ShapeKind::Star => Box::new(Star),
+            ShapeKind::Rose => Box::new(Rose {
+                petals: self.petals,
+            }),
+            ShapeKind::Polyline => Box::new(Polyline {
+                vertices: self.points.clone(),
+            }),
+        }
+    }
+
+    /// Switch to the next shape, skipping `polyline` when no points are configured.
+    pub fn cycle_shape(&mut self) {
+        self.shape = self.shape.next();
+        if self.shape == ShapeKind::Polyline && self.points.len() < 3 {
+            self.shape = self.shape.next();
+        }
+    }
+
+    /// The heartbeat described by `bpm`, `pulse` and `easing`.
+    pub fn heartbeat(&self) -> Heartbeat {
+        Heartbeat {
+            bpm: self.bpm,
+            amplitude: self.pulse,
+            easing: self.easing,
+        }
+    }
+
+    /// Whether a countdown may be on screen, ticking with the wall clock.
+    pub fn counts_down(&self) -> bool {
+        match self.scenes.as_slice() {
+            [] => self.countdown,
+            scenes => scenes.iter().any(|s| s.kind == SceneKind::Countdown),
+        }
+    }
+
+    /// Time between animation frames.
+    pub fn tick_rate(&self) -> Duration {
+        Duration::from_secs_f64(1.0 / self.fps)
+    }
+
+    /// The active palette as a cyclic gradient.
+    ///
+    /// Explicit `colors` win over a user-defined palette, which wins over a
+    /// built-in one of the same name.
+    pub fn gradient(&self) -> Gradient {
+        let user = match self.colors.as_slice() {
+            [] => self.palettes.get(&self.palette).map(Vec::as_slice),
+            colors => Some(colors),
+        };
+        match user {
+            Some(colors) => {
+                let stops: Vec<_> = colors.iter().copied().map(palette::rgb).collect();
+                Gradient::new(&stops, self.interpolation)
+            }
+            None => {
+                let palette = Palette::from_str(&self.palette, true).unwrap_or_default();
+                Gradient::new(palette.stops(), self.interpolation)
+            }
+        }
+    }
+
+    /// Number of ticks in one trip through the palette, at least one.
+    pub fn cycle_frames(&self) -> u64 {
+        (self.cycle * self.fps).round().max(1.0) as u64
+    }
+
+    /// Position in the palette after `seconds` of animation.
+    pub fn phase(&self, seconds: f64) -> f64 {
+        seconds / self.cycle
+    }
+
+    /// Pick the color after `seconds` of animation.
+    pub fn color(&self, seconds: f64) -> Color {
+        self.quantize(self.gradient().sample(self.phase(seconds)))
+    }
+
+    /// Reduce `color` to what the terminal can show.
+    pub fn quantize(&self, color: Color) -> Color {
+        self.color_depth.quantize(color)
+    }
+}
+
+/// Default location of the config file, `$XDG_CONFIG_HOME/ratatui_heart/config.toml`.
+pub fn default_path() -> Option<PathBuf> {
+    let base = env::var_os("XDG_CONFIG_HOME")
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))?;
+    Some(base.join("ratatui_heart").join("config.toml"))
+}
+
+/// Parse a duration written as plain seconds or with an `ms`, `s`, `m` or `h` suffix.
+pub fn parse_duration(s: &str) -> Result<Duration> {
+    let s = s.trim();
+    let split = s.find(|c: char| c.is_ascii_alphabetic()).unwrap_or(s.len());
+    let (value, unit) = s.split_at(split);
+    let value: f64 = value
+        .parse()
+        .map_err(|_| eyre!("`{s}` does not start with a number"))?;
+    let seconds = match unit {
+        "ms" => value / 1000.0,
+        "" | "s" => value,
+        "m" => value * 60.0,
+        "h" => value * 3600.0,
+        _ => bail!("unknown unit `{unit}`, expected one of ms, s, m, h"),
+    };
+    Duration::try_from_secs_f64(seconds).wrap_err_with(|| format!("`{s}` is not a valid duration"))
+}
+
+pub(crate) fn deserialize_duration<'de, D: Deserializer<'de>>(
+    de: D,
+) -> Result<Option<Duration>, D::Error> {
+    let Some(s) = Option::<String>::deserialize(de)? else {
+        return Ok(None);
+    };
+    parse_duration(&s)
+        .map(Some)
+        .map_err(|err| serde::de::Error::custom(format!("{err:#}")))
+}
+
+/// Accept the target as a string or a bare TOML date or local date-time.
+fn deserialize_target<'de, D: Deserializer<'de>>(de: D) -> Result<Option<NaiveDateTime>, D::Error> {
+    let text = match Option::<toml::Value>::deserialize(de)? {
+        None => return Ok(None),
+        Some(toml::Value::String(s)) => s,
+        Some(toml::Value::Datetime(datetime)) => datetime.to_string(),
+        Some(other) => {
+            return Err(serde::de::Error::custom(format!(
+                "expected a date, got {}",
+                other.type_str()
+            )));
+        }
+    };
+    countdown::parse_target(&text)
+        .map(Some)
+        .map_err(|err| serde::de::Error::custom(format!("{err:#}")))
+}
+
+fn deserialize_required_duration<'de, D: Deserializer<'de>>(de: D) -> Result<Duration, D::Error> {
+    let s = String::deserialize(de)?;
+    parse_duration(&s).map_err(|err| serde::de::Error::custom(format!("{err:#}")))
+}
+
+/// Watches the config file and reloads it whenever its modification time changes.
+#[derive(Debug)]
+pub struct ConfigWatcher {
+    path: PathBuf,
+    modified: Option<SystemTime>,
+    last_check: Instant,
+}
+
+impl ConfigWatcher {
+    /// How often the file's modification time is checked.
+    const INTERVAL: Duration = Duration::from_millis(500);
+
+    /// Start watching `path`, treating its current contents as already loaded.
+    pub fn new(path: PathBuf) -> Self {
+        let modified = modified(&path);
+        Self {
+            path,
+            modified,
+            last_check: Instant::now(),
+        }
+    }
+
+    /// Path of the watched file.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Reload the file if it changed since the last call.
+    ///
+    /// Returns `None` when nothing changed, otherwise the freshly parsed config.
+    pub fn poll(&mut self) -> Option<Result<Config>> {
+        if self.last_check.elapsed() < Self::INTERVAL {
+            return None;
+        }
+        self.last_check = Instant::now();
+
+        let modified = modified(&self.path);
+        if modified == self.modified {
+            return None;
+        }
+        self.modified = modified;
+
+        // A deleted file means "back to defaults" rather than an error
+        if modified.is_none() {
+            return Some(Ok(Config::default()));
+        }
+        Some(Config::load(&self.path))
+    }
+}
+
+fn modified(path: &Path) -> Option<SystemTime> {
+    fs::metadata(path).and_then(|meta| meta.modified()).ok()
+}
@@ -0,0 +1,68 @@
+// src/countdown.rs
+//! Counting down to a moment in local time, by default the next Valentine's Day.
+//!
+//! Times are naive local date-times, so the count ignores daylight saving
+//! changes, just like a wall calendar would.
+
+use std::time::Duration;
+
+use chrono::{Datelike, Local, NaiveDate, NaiveDateTime, NaiveTime};
+use color_eyre::{Result, eyre::eyre};
+
+/// The current local date and time.
+pub fn now() -> NaiveDateTime {
+    Local::now().naive_local()
+}
+
+/// Midnight at the start of the next 14 February, or of today if that is the
+/// 14th, so the whole day counts as arrived.
+pub fn next_valentines(now: NaiveDateTime) -> NaiveDateTime {
+    let valentines = |year| {
+        NaiveDate::from_ymd_opt(year, 2, 14)
+            .expect("14 February exists every year")
+            .and_time(NaiveTime::MIN)
+    };
+    let this_year = valentines(now.year());
+    if now.date() <= this_year.date() {
+        this_year
+    } else {
+        valentines(now.year() + 1)
+    }
+}
+
+/// Time left from `now` until `target`, or the next Valentine's Day when
+/// unset; zero once it has passed.
+pub fn remaining(target: Option<NaiveDateTime>, now: NaiveDateTime) -> Duration {
+    let target = target.unwrap_or_else(|| next_valentines(now));
+    (target - now).to_std().unwrap_or_default()
+}
+
+/// `remaining` as days, hours, minutes and seconds, e.g. `3d 04h 05m 06s`,
+/// leaving out the days on the last one.
+pub fn label(remaining: Duration) -> String {
+    let seconds = remaining.as_secs();
+    let (days, hours) = (seconds / 86_400, seconds / 3600 % 24);
+    let (minutes, seconds) = (seconds / 60 % 60, seconds % 60);
+    match days {
+        0 => format!("{hours:02}h {minutes:02}m {seconds:02}s"),
+        days => format!("{days}d {hours:02}h {minutes:02}m {seconds:02}s"),
+    }
+}
+
+/// Parse a local target written as `YYYY-MM-DD`, optionally followed by a
+/// time as `HH:MM` or `HH:MM:SS` after a space or `T`.
+pub fn parse_target(s: &str) -> Result<NaiveDateTime> {
+    let s = s.trim();
+    if let Ok(date) = NaiveDate::parse_from_str(s, "%Y-%m-%d") {
+        return Ok(date.and_time(NaiveTime::MIN));
+    }
+    [
+        "%Y-%m-%d %H:%M:%S",
+        "%Y-%m-%dT%H:%M:%S",
+        "%Y-%m-%d %H:%M",
+        "%Y-%m-%dT%H:%M",
+    ]
+    .iter()
+    .find_map(|format| NaiveDateTime::parse_from_str(s, format).ok())
+    .ok_or_else(|| eyre!("`{s}` is not a date like 2027-02-14 or 2027-02-14 18:30"))
+}
@@ -0,0 +1,7 @@
+// src/export.rs
+//! Exporting the animation to files, rendered headlessly frame by frame.
+
+pub mod cast;
+pub mod gif;
+pub mod raster;
+pub mod svg;
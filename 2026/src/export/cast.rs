@@ -0,0 +1,48 @@
+// src/export/cast.rs
+//! asciinema v2 `.cast` export.
+//!
+//! The file is newline-delimited JSON: a header with the terminal size, then one
+//! `[seconds, "o", output]` event per frame that redraws the whole screen.
+
+use std::io::Write;
+
+use color_eyre::Result;
+use serde_json::json;
+
+use crate::{config::Config, headless};
+
+/// Hide the cursor and clear the screen before the first frame.
+const START: &str = "\x1b[?25l\x1b[2J\x1b[H";
+/// Move the cursor home before every later frame.
+const HOME: &str = "\x1b[H";
+
+/// Render `frames` consecutive ticks starting at `first` and write them as an
+/// asciinema recording, one tick of `config.fps` apart.
+pub fn write_cast(
+    config: &Config,
+    first: u64,
+    frames: u64,
+    (width, height): (u16, u16),
+    mut writer: impl Write,
+) -> Result<()> {
+    let header = json!({
+        "version": 2,
+        "width": width,
+        "height": height,
+        "env": { "TERM": "xterm-256color" },
+    });
+    writeln!(writer, "{header}")?;
+
+    let buffers = headless::render_frames(config, first, frames, width, height);
+    for (index, buffer) in buffers.enumerate() {
+        let ansi = headless::to_ansi(&buffer);
+        // A newline after the last row would scroll the screen, and the player
+        // expects carriage returns as a raw-mode terminal would
+        let rows = ansi.trim_end_matches('\n').replace('\n', "\r\n");
+        let output = format!("{}{rows}", if index == 0 { START } else { HOME });
+        let seconds = index as f64 / config.fps;
+        writeln!(writer, "{}", json!([seconds, "o", output]))?;
+    }
+    writer.flush()?;
+    Ok(())
+}
@@ -0,0 +1,68 @@
+// src/export/gif.rs
+//! Animated GIF export.
+
+use std::{collections::HashMap, io::Write};
+
+use color_eyre::{Result, eyre::ensure};
+use gif::{Encoder, Frame, Repeat};
+
+use crate::{config::Config, export::raster, headless};
+
+/// Render `frames` consecutive ticks starting at `first` and encode them as a
+/// looping GIF, each frame shown for one tick of `config.fps`.
+pub fn write_gif(
+    config: &Config,
+    first: u64,
+    frames: u64,
+    (width, height): (u16, u16),
+    writer: impl Write,
+) -> Result<()> {
+    let pixel_width = usize::from(width) * raster::CELL_WIDTH;
+    let pixel_height = usize::from(height) * raster::CELL_HEIGHT;
+    ensure!(
+        pixel_width <= usize::from(u16::MAX) && pixel_height <= usize::from(u16::MAX),
+        "{width}x{height} cells is too large for a GIF"
+    );
+    let (pixel_width, pixel_height) = (pixel_width as u16, pixel_height as u16);
+
+    let mut encoder = Encoder::new(writer, pixel_width, pixel_height, &[])?;
+    encoder.set_repeat(Repeat::Infinite)?;
+
+    // GIF delays are in hundredths of a second
+    let delay = (100.0 / config.fps).round().max(2.0) as u16;
+
+    for buffer in headless::render_frames(config, first, frames, width, height) {
+        let image = raster::rasterize(&buffer);
+        let mut frame = encode_frame(&image, pixel_width, pixel_height);
+        frame.delay = delay;
+        encoder.write_frame(&frame)?;
+    }
+    Ok(())
+}
+
+/// Build a frame with an exact palette when the image has at most 256 colors,
+/// falling back to NeuQuant quantization otherwise.
+fn encode_frame(image: &raster::Image, width: u16, height: u16) -> Frame<'static> {
+    let mut palette: HashMap<(u8, u8, u8), u8> = HashMap::new();
+    let mut indices = Vec::with_capacity(image.pixels.len());
+    for &pixel in &image.pixels {
+        let next = palette.len();
+        let index = *palette.entry(pixel).or_insert(next.min(255) as u8);
+        if palette.len() > 256 {
+            let rgb: Vec<u8> = image
+                .pixels
+                .iter()
+                .flat_map(|&(r, g, b)| [r, g, b])
+                .collect();
+            return Frame::from_rgb_speed(width, height, &rgb, 10);
+        }
+        indices.push(index);
+    }
+
+    let mut colors = vec![0; palette.len() * 3];
+    for ((r, g, b), index) in palette {
+        let i = usize::from(index) * 3;
+        colors[i..i + 3].copy_from_slice(&[r, g, b]);
+    }
+    Frame::from_palette_pixels(width, height, indices, colors, None)
+}
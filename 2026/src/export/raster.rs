@@ -0,0 +1,179 @@
+// src/export/raster.rs
+//! Turning a rendered cell buffer into RGB pixels.
+//!
+//! Braille cells are drawn as round-ish colored dots so the canvas looks like it
+//! does in a terminal; every other symbol uses the built-in 8x8 bitmap font,
+//! stretched to the cell height.
+
+use font8x8::{BASIC_FONTS, BLOCK_FONTS, BOX_FONTS, LATIN_FONTS, UnicodeFonts};
+use ratatui::{buffer::Buffer, style::Color};
+
+use crate::palette;
+
+/// Width of one terminal cell in pixels.
+pub const CELL_WIDTH: usize = 8;
+/// Height of one terminal cell in pixels.
+pub const CELL_HEIGHT: usize = 16;
+
+/// Color behind cells with the default background.
+pub const BACKGROUND: (u8, u8, u8) = (0, 0, 0);
+/// Color of symbols with the default foreground.
+pub const FOREGROUND: (u8, u8, u8) = (229, 229, 229);
+
+/// An RGB image.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Image {
+    /// Width in pixels.
+    pub width: usize,
+    /// Height in pixels.
+    pub height: usize,
+    /// Row-major pixels.
+    pub pixels: Vec<(u8, u8, u8)>,
+}
+
+impl Image {
+    fn fill_rect(&mut self, x: usize, y: usize, width: usize, height: usize, color: (u8, u8, u8)) {
+        for row in y..(y + height).min(self.height) {
+            let start = row * self.width;
+            for col in x..(x + width).min(self.width) {
+                self.pixels[start + col] = color;
+            }
+        }
+    }
+}
+
+/// Rasterize every cell of `buffer`.
+pub fn rasterize(buffer: &Buffer) -> Image {
+    let (cols, rows) = (
+        usize::from(buffer.area.width),
+        usize::from(buffer.area.height),
+    );
+    let mut image = Image {
+        width: cols * CELL_WIDTH,
+        height: rows * CELL_HEIGHT,
+        pixels: vec![BACKGROUND; cols * CELL_WIDTH * rows * CELL_HEIGHT],
+    };
+
+    for (index, cell) in buffer.content.iter().enumerate() {
+        let (x, y) = ((index % cols) * CELL_WIDTH, (index / cols) * CELL_HEIGHT);
+        let fg = color_rgb(cell.fg, FOREGROUND);
+        let bg = color_rgb(cell.bg, BACKGROUND);
+        image.fill_rect(x, y, CELL_WIDTH, CELL_HEIGHT, bg);
+
+        let Some(symbol) = cell.symbol().chars().next() else {
+            continue;
+        };
+        if let Some(dots) = braille_dots(symbol) {
+            draw_braille(&mut image, x, y, dots, fg);
+        } else if let Some(cells) = sextant_cells(symbol) {
+            draw_sextant(&mut image, x, y, cells, fg);
+        } else if symbol == '•' {
+            image.fill_rect(x + CELL_WIDTH / 3, y + CELL_HEIGHT * 3 / 8, 3, 3, fg);
+        } else if let Some(glyph) = glyph(symbol) {
+            draw_glyph(&mut image, x, y, glyph, fg);
+        }
+    }
+    image
+}
+
+/// RGB value of a cell color, with `Reset` meaning `default`.
+pub fn color_rgb(color: Color, default: (u8, u8, u8)) -> (u8, u8, u8) {
+    match color {
+        Color::Reset => default,
+        color => palette::rgb(color),
+    }
+}
+
+/// The raised dots of a braille pattern, bit `n` being dot `n + 1`.
+fn braille_dots(symbol: char) -> Option<u8> {
+    let offset = u32::from(symbol).checked_sub(0x2800)?;
+    u8::try_from(offset).ok()
+}
+
+/// Draw the dots of a braille cell as 2 columns by 4 rows of small squares.
+fn draw_braille(image: &mut Image, x: usize, y: usize, dots: u8, color: (u8, u8, u8)) {
+    // Dot numbering: 1 4 / 2 5 / 3 6 / 7 8
+    const POSITIONS: [(usize, usize); 8] = [
+        (0, 0),
+        (0, 1),
+        (0, 2),
+        (1, 0),
+        (1, 1),
+        (1, 2),
+        (0, 3),
+        (1, 3),
+    ];
+    const DOT: usize = 3;
+    for (bit, &(col, row)) in POSITIONS.iter().enumerate() {
+        if dots & (1 << bit) != 0 {
+            let dx = col * CELL_WIDTH / 2 + (CELL_WIDTH / 2 - DOT) / 2;
+            let dy = row * CELL_HEIGHT / 4 + (CELL_HEIGHT / 4 - DOT) / 2;
+            image.fill_rect(x + dx, y + dy, DOT, DOT, color);
+        }
+    }
+}
+
+/// The filled cells of a sextant block, bit `n` being cell `n + 1` counted
+/// left to right, top to bottom.
+fn sextant_cells(symbol: char) -> Option<u8> {
+    let offset = u32::from(symbol).checked_sub(0x1FB00)?;
+    if offset >= 60 {
+        return None;
+    }
+    // The block skips the patterns that already exist as half blocks
+    let mut cells = offset as u8 + 1;
+    if cells >= 21 {
+        cells += 1;
+    }
+    if cells >= 42 {
+        cells += 1;
+    }
+    Some(cells)
+}
+
+/// Draw a sextant block as 2 columns by 3 rows of filled rectangles.
+fn draw_sextant(image: &mut Image, x: usize, y: usize, cells: u8, color: (u8, u8, u8)) {
+    for bit in 0..6 {
+        if cells & (1 << bit) != 0 {
+            let (col, row) = (bit % 2, bit / 2);
+            let (top, bottom) = (row * CELL_HEIGHT / 3, (row + 1) * CELL_HEIGHT / 3);
+            image.fill_rect(
+                x + col * CELL_WIDTH / 2,
+                y + top,
+                CELL_WIDTH / 2,
+                bottom - top,
+                color,
+            );
+        }
+    }
+}
+
+/// The 8x8 bitmap of `symbol`, if the built-in font has one.
+fn glyph(symbol: char) -> Option<[u8; 8]> {
+    if symbol == ' ' {
+        return None;
+    }
+    BASIC_FONTS
+        .get(symbol)
+        .or_else(|| BLOCK_FONTS.get(symbol))
+        .or_else(|| BOX_FONTS.get(symbol))
+        .or_else(|| LATIN_FONTS.get(symbol))
+}
+
+/// Draw an 8x8 glyph stretched to the cell, bit 0 of each row being leftmost.
+fn draw_glyph(image: &mut Image, x: usize, y: usize, glyph: [u8; 8], color: (u8, u8, u8)) {
+    let scale_y = CELL_HEIGHT / 8;
+    for (row, bits) in glyph.iter().enumerate() {
+        for col in 0..8 {
+            if bits & (1 << col) != 0 {
+                image.fill_rect(
+                    x + col * CELL_WIDTH / 8,
+                    y + row * scale_y,
+                    CELL_WIDTH / 8,
+                    scale_y,
+                    color,
+                );
+            }
+        }
+    }
+}
@@ -0,0 +1,151 @@
+// src/export/svg.rs
+//! Self-animating SVG export.
+//!
+//! Every frame is laid out side by side on one strip, and a CSS `steps()`
+//! animation slides the strip one frame width at a time, so the file plays in
+//! any browser without scripts.
+
+use std::{fmt::Write as _, io::Write};
+
+use color_eyre::Result;
+use ratatui::{buffer::Buffer, style::Color};
+
+use crate::{
+    config::Config,
+    export::raster::{self, BACKGROUND, FOREGROUND},
+    headless,
+};
+
+/// Width of one terminal cell in SVG user units.
+pub const CELL_WIDTH: usize = 9;
+/// Height of one terminal cell in SVG user units.
+pub const CELL_HEIGHT: usize = 18;
+/// Font size that gives a monospace advance of about one cell width.
+const FONT_SIZE: usize = 15;
+/// Distance from the top of a cell to the text baseline.
+const BASELINE: usize = 14;
+
+/// Render `frames` consecutive ticks starting at `first` and write them as an
+/// SVG that loops through them, each shown for one tick of `config.fps`.
+pub fn write_svg(
+    config: &Config,
+    first: u64,
+    frames: u64,
+    (width, height): (u16, u16),
+    mut writer: impl Write,
+) -> Result<()> {
+    let frame_width = usize::from(width) * CELL_WIDTH;
+    let frame_height = usize::from(height) * CELL_HEIGHT;
+
+    writeln!(
+        writer,
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="{frame_width}" height="{frame_height}" viewBox="0 0 {frame_width} {frame_height}">"#
+    )?;
+    writeln!(writer, "<style>")?;
+    writeln!(
+        writer,
+        "text{{font-family:'DejaVu Sans Mono',Menlo,Consolas,monospace;font-size:{FONT_SIZE}px;white-space:pre}}"
+    )?;
+    if frames > 1 {
+        let seconds = frames as f64 / config.fps;
+        let strip = frames as usize * frame_width;
+        writeln!(
+            writer,
+            ".strip{{animation:play {seconds:.3}s steps({frames}) infinite}}"
+        )?;
+        writeln!(
+            writer,
+            "@keyframes play{{to{{transform:translateX(-{strip}px)}}}}"
+        )?;
+    }
+    writeln!(writer, "</style>")?;
+    writeln!(
+        writer,
+        r#"<rect width="100%" height="100%" fill="{}"/>"#,
+        hex(BACKGROUND)
+    )?;
+
+    writeln!(writer, r#"<g class="strip">"#)?;
+    let buffers = headless::render_frames(config, first, frames, width, height);
+    for (index, buffer) in buffers.enumerate() {
+        writeln!(
+            writer,
+            r#"<g transform="translate({},0)">"#,
+            index * frame_width
+        )?;
+        writer.write_all(frame(&buffer).as_bytes())?;
+        writeln!(writer, "</g>")?;
+    }
+    writeln!(writer, "</g>")?;
+    writeln!(writer, "</svg>")?;
+    writer.flush()?;
+    Ok(())
+}
+
+/// SVG elements for one frame: background rectangles, then one `<text>` per run
+/// of visible symbols sharing a color.
+fn frame(buffer: &Buffer) -> String {
+    let cols = usize::from(buffer.area.width).max(1);
+    let mut svg = String::new();
+
+    for (row, cells) in buffer.content.chunks(cols).enumerate() {
+        for (col, cell) in cells.iter().enumerate() {
+            if cell.bg != Color::Reset {
+                let _ = writeln!(
+                    svg,
+                    r#"<rect x="{}" y="{}" width="{CELL_WIDTH}" height="{CELL_HEIGHT}" fill="{}"/>"#,
+                    col * CELL_WIDTH,
+                    row * CELL_HEIGHT,
+                    hex(raster::color_rgb(cell.bg, BACKGROUND))
+                );
+            }
+        }
+
+        let mut col = 0;
+        while col < cells.len() {
+            if is_blank(cells[col].symbol()) {
+                col += 1;
+                continue;
+            }
+            let fg = cells[col].fg;
+            let start = col;
+            let mut text = String::new();
+            while col < cells.len() && cells[col].fg == fg && !is_blank(cells[col].symbol()) {
+                escape(cells[col].symbol(), &mut text);
+                col += 1;
+            }
+            // Pin the run to the cell grid whatever the font's advance is
+            let _ = writeln!(
+                svg,
+                r#"<text x="{}" y="{}" fill="{}" textLength="{}">{text}</text>"#,
+                start * CELL_WIDTH,
+                row * CELL_HEIGHT + BASELINE,
+                hex(raster::color_rgb(fg, FOREGROUND)),
+                (col - start) * CELL_WIDTH
+            );
+        }
+    }
+    svg
+}
+
+/// Whether a cell shows nothing in the foreground color.
+fn is_blank(symbol: &str) -> bool {
+    symbol.chars().all(|c| c == ' ' || c == '\u{2800}')
+}
+
+/// Append `symbol` with XML special characters escaped.
+fn escape(symbol: &str, out: &mut String) {
+    for c in symbol.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            c => out.push(c),
+        }
+    }
+}
+
+/// `#rrggbb` notation of an RGB color.
+fn hex((r, g, b): (u8, u8, u8)) -> String {
+    format!("#{r:02x}{g:02x}{b:02x}")
+}
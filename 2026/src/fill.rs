@@ -0,0 +1,118 @@
+// src/fill.rs
+//! Scanline rasterization of closed outlines onto the canvas dot grid.
+
+use clap::ValueEnum;
+use serde::Deserialize;
+
+use crate::shape::Point;
+
+/// How the inside of a filled shape is colored.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum FillStyle {
+    /// A single fill color.
+    #[default]
+    Solid,
+    /// A vertical blend between the two `gradient` colors.
+    Gradient,
+}
+
+/// One row of filled dots on the canvas grid.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Row {
+    /// World y coordinate of the row.
+    pub y: f64,
+    /// Dot centres inside the outlines.
+    pub points: Vec<Point>,
+}
+
+/// Collect the dot centres of a `cols` x `rows` grid spanning the given bounds
+/// that lie inside `outlines`.
+///
+/// Insideness uses the non-zero winding rule, so overlapping outlines and
+/// curves that trace themselves twice (like odd rose curves) fill solidly.
+pub fn rasterize(
+    outlines: &[Vec<Point>],
+    x_bounds: [f64; 2],
+    y_bounds: [f64; 2],
+    cols: usize,
+    rows: usize,
+) -> Vec<Row> {
+    if cols == 0 || rows == 0 {
+        return Vec::new();
+    }
+    let dx = (x_bounds[1] - x_bounds[0]) / cols as f64;
+    let dy = (y_bounds[1] - y_bounds[0]) / rows as f64;
+
+    let mut filled = Vec::new();
+    let mut crossings = Vec::new();
+    for row in 0..rows {
+        // Sample at dot centres, top row first
+        let y = y_bounds[1] - (row as f64 + 0.5) * dy;
+        crossings.clear();
+        for outline in outlines {
+            collect_crossings(outline, y, &mut crossings);
+        }
+        if crossings.is_empty() {
+            continue;
+        }
+        crossings.sort_by(|a, b| a.0.total_cmp(&b.0));
+
+        let mut points = Vec::new();
+        let mut winding = 0;
+        for pair in crossings.windows(2) {
+            let ((start, dir), (end, _)) = (pair[0], pair[1]);
+            winding += dir;
+            if winding == 0 {
+                continue;
+            }
+            let first = ((start - x_bounds[0]) / dx - 0.5).ceil().max(0.0) as usize;
+            let last = ((end - x_bounds[0]) / dx - 0.5).floor();
+            if last < 0.0 {
+                continue;
+            }
+            let last = (last as usize).min(cols - 1);
+            points.extend((first..=last).map(|col| (x_bounds[0] + (col as f64 + 0.5) * dx, y)));
+        }
+        if !points.is_empty() {
+            filled.push(Row { y, points });
+        }
+    }
+    filled
+}
+
+/// Record where the closed polygon `outline` crosses the horizontal line at `y`,
+/// with +1 for upward and -1 for downward edges.
+fn collect_crossings(outline: &[Point], y: f64, crossings: &mut Vec<(f64, i32)>) {
+    let Some(&last) = outline.last() else {
+        return;
+    };
+    let mut prev = last;
+    for &point in outline {
+        let ((x0, y0), (x1, y1)) = (prev, point);
+        prev = point;
+
+        // Half-open test so shared vertices are only counted once
+        let dir = if y0 <= y && y < y1 {
+            1
+        } else if y1 <= y && y < y0 {
+            -1
+        } else {
+            continue;
+        };
+        let x = x0 + (y - y0) / (y1 - y0) * (x1 - x0);
+        crossings.push((x, dir));
+    }
+}
+
+/// Vertical extent `(top, bottom)` of the outlines, if they have any points.
+pub fn vertical_extent(outlines: &[Vec<Point>]) -> Option<(f64, f64)> {
+    outlines
+        .iter()
+        .flatten()
+        .map(|&(_, y)| y)
+        .fold(None, |extent, y| match extent {
+            None => Some((y, y)),
+            Some((top, bottom)) => Some((f64::max(top, y), f64::min(bottom, y))),
+        })
+}
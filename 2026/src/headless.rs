@@ -0,0 +1,140 @@
+// src/headless.rs
+//! Rendering frames without a terminal, as plain text or ANSI escape sequences.
+
+use std::fmt::Write;
+
+use clap::ValueEnum;
+use ratatui::{Terminal, backend::TestBackend, buffer::Buffer, style::Color};
+
+use crate::{config::Config, scene::Playlist, view::View};
+
+/// Output format of a headless frame.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum Format {
+    /// Symbols only, with trailing spaces trimmed.
+    #[default]
+    Text,
+    /// Symbols with SGR color escape sequences.
+    Ansi,
+}
+
+impl Format {
+    /// Convert a rendered buffer to this format.
+    pub fn encode(self, buffer: &Buffer) -> String {
+        match self {
+            Format::Text => to_text(buffer),
+            Format::Ansi => to_ansi(buffer),
+        }
+    }
+}
+
+/// Render the frame for `tick` into a `width` x `height` buffer, without particles.
+///
+/// Time-based effects see `tick / fps` seconds, so the same tick always
+/// produces the same frame.
+pub fn render_frame(config: &Config, tick: u64, width: u16, height: u16) -> Buffer {
+    let mut playlist = Playlist::new(config);
+    render(
+        config,
+        &mut playlist,
+        tick as f64 / config.fps,
+        width,
+        height,
+    )
+}
+
+/// Render `frames` consecutive ticks starting at `first`, stepping the
+/// scenes and their particles one tick between frames.
+///
+/// The first frame matches [`render_frame`]; particles appear from the second.
+pub fn render_frames(
+    config: &Config,
+    first: u64,
+    frames: u64,
+    width: u16,
+    height: u16,
+) -> impl Iterator<Item = Buffer> {
+    let mut playlist = Playlist::new(config);
+    (0..frames).map(move |index| {
+        // Ticks wrap rather than overflow past the largest seed
+        let seconds = first.wrapping_add(index) as f64 / config.fps;
+        if index > 0 {
+            playlist.update(config, &View::default(), seconds, 1.0 / config.fps);
+        }
+        render(config, &mut playlist, seconds, width, height)
+    })
+}
+
+fn render(
+    config: &Config,
+    playlist: &mut Playlist,
+    seconds: f64,
+    width: u16,
+    height: u16,
+) -> Buffer {
+    let backend = TestBackend::new(width, height);
+    let mut terminal = Terminal::new(backend).expect("test backend never fails");
+    terminal
+        .draw(|frame| playlist.render(frame, config, &View::default(), seconds))
+        .expect("test backend never fails");
+    terminal.backend().buffer().clone()
+}
+
+/// The buffer's symbols, one line per row, with trailing spaces trimmed.
+pub fn to_text(buffer: &Buffer) -> String {
+    let mut text = String::new();
+    for row in buffer.content.chunks(usize::from(buffer.area.width).max(1)) {
+        let line: String = row.iter().map(|cell| cell.symbol()).collect();
+        text.push_str(line.trim_end());
+        text.push('\n');
+    }
+    text
+}
+
+/// The buffer's symbols with foreground and background colors as SGR sequences.
+///
+/// Every line ends with a reset, so frames can be printed one after another.
+pub fn to_ansi(buffer: &Buffer) -> String {
+    let mut ansi = String::new();
+    for row in buffer.content.chunks(usize::from(buffer.area.width).max(1)) {
+        let mut current = None;
+        for cell in row {
+            if current != Some((cell.fg, cell.bg)) {
+                ansi.push_str("\x1b[0");
+                push_sgr(&mut ansi, cell.fg, false);
+                push_sgr(&mut ansi, cell.bg, true);
+                ansi.push('m');
+                current = Some((cell.fg, cell.bg));
+            }
+            ansi.push_str(cell.symbol());
+        }
+        ansi.push_str("\x1b[0m\n");
+    }
+    ansi
+}
+
+/// Append the SGR parameters selecting `color`, if it is not the default.
+fn push_sgr(out: &mut String, color: Color, background: bool) {
+    let base = if background { 40 } else { 30 };
+    let _ = match color {
+        Color::Reset => Ok(()),
+        Color::Black => write!(out, ";{base}"),
+        Color::Red => write!(out, ";{}", base + 1),
+        Color::Green => write!(out, ";{}", base + 2),
+        Color::Yellow => write!(out, ";{}", base + 3),
+        Color::Blue => write!(out, ";{}", base + 4),
+        Color::Magenta => write!(out, ";{}", base + 5),
+        Color::Cyan => write!(out, ";{}", base + 6),
+        Color::Gray => write!(out, ";{}", base + 7),
+        Color::DarkGray => write!(out, ";{}", base + 60),
+        Color::LightRed => write!(out, ";{}", base + 61),
+        Color::LightGreen => write!(out, ";{}", base + 62),
+        Color::LightYellow => write!(out, ";{}", base + 63),
+        Color::LightBlue => write!(out, ";{}", base + 64),
+        Color::LightMagenta => write!(out, ";{}", base + 65),
+        Color::LightCyan => write!(out, ";{}", base + 66),
+        Color::White => write!(out, ";{}", base + 67),
+        Color::Indexed(index) => write!(out, ";{};5;{index}", base + 8),
+        Color::Rgb(r, g, b) => write!(out, ";{};2;{r};{g};{b}", base + 8),
+    };
+}
@@ -0,0 +1,29 @@
+// src/lib.rs
+//! Valentine's Day Rainbow Heart - the rendering core behind the TUI.
+//!
+//! [`widget::HeartWidget`] draws the heart for a [`config::Config`] at a
+//! given animation time into any area of a ratatui layout,
+//! [`scene::Playlist`] strings such frames together into a show, and
+//! [`headless`] renders either into an off-screen buffer for dumps, exports
+//! and snapshot tests.
+
+pub mod board;
+pub mod clock;
+pub mod config;
+pub mod countdown;
+pub mod export;
+pub mod fill;
+pub mod headless;
+pub mod marker;
+pub mod message;
+pub mod palette;
+pub mod particles;
+pub mod pulse;
+pub mod render;
+pub mod scene;
+pub mod screensaver;
+pub mod shape;
+pub mod solid;
+pub mod sync;
+pub mod view;
+pub mod widget;
@@ -1,137 +1,417 @@
 // src/main.rs
 //! Valentine's Day Rainbow Heart TUI - Ratatui + Crossterm
 //! Draws an animated, thick heart with cycling rainbow colors.
-//! Press 'q' or ESC to quit.
+//! Press 's' to cycle shapes, 'm' to cycle markers, 'f' to toggle the fill, '3' to toggle the solid heart,
+//! space to pause, '+'/'-' to change speed, '.' to step, 'r' to reverse, '0' to reset the view, 'n'/'p' for the
+//! next or previous scene, any other key for confetti, 'q' or ESC to quit.
+//! Click for hearts, drag to move the heart (right button to turn it) and scroll to zoom.
+//! As a screensaver the heart wanders about until any key or mouse input.
+//! With `--listen` it shows notes sent over a local socket.
+//! With `--sync` several terminals animate in step, and `--tile` splits one heart across them.
+//! Run with `--help` for tuning options.
 
-use std::io;
+mod cli;
+mod settings;
+mod tui;
+
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
 use std::time::{Duration, Instant};
 
-use color_eyre::Result;
-use crossterm::{
-    event::{self, Event, KeyCode},
-    terminal::{disable_raw_mode, enable_raw_mode},
+use color_eyre::{
+    Result,
+    eyre::{WrapErr, eyre},
+};
+use crossterm::event::{
+    self, Event, KeyCode, KeyEventKind, KeyModifiers, MouseButton, MouseEvent, MouseEventKind,
 };
-use ratatui::{
-    backend::CrosstermBackend,
-    style::Color,
-    widgets::canvas::{Canvas, Context, Points},
-    Frame, Terminal,
+use ratatui::layout::Rect;
+use ratatui_heart::board::{Board, Listener, NoteEffect};
+use ratatui_heart::clock::Clock;
+use ratatui_heart::config::Config;
+use ratatui_heart::countdown;
+use ratatui_heart::particles::Particles;
+use ratatui_heart::render::{
+    canvas_area, cell_to_world, draw_error, draw_note, draw_status, world_bounds,
 };
+use ratatui_heart::scene::Playlist;
+use ratatui_heart::screensaver::Screensaver;
+use ratatui_heart::shape::Point;
+use ratatui_heart::sync::{Beat, Follower, Leader, Role};
+use ratatui_heart::view::View;
+use ratatui_heart::{export, headless};
+
+use crate::cli::{Cli, Headless, Target};
+use crate::settings::Settings;
+use crate::tui::{Signals, Tui};
 
 fn main() -> Result<()> {
-    // Initialize error handling and terminal
-    color_eyre::install()?;
-    enable_raw_mode()?;
+    // Initialize error handling and parse arguments before touching the terminal
+    tui::install_hooks()?;
+    let cli = Cli::load()?;
+    let headless = cli.headless();
+    let settings = Settings::load(cli)?;
+
+    if let Some(headless) = headless {
+        if let Some(err) = settings.error {
+            return Err(eyre!(err));
+        }
+        return dump(&settings.config, headless);
+    }
 
-    let mut stdout = io::stdout();
-    let backend = CrosstermBackend::new(&mut stdout);
-    let mut terminal = Terminal::new(backend)?;
+    let signals = Signals::register()?;
+    let mut terminal = tui::init()?;
 
-    terminal.clear()?;
-    
     // Run the main event loop
-    let res = run(&mut terminal);
-    
-    // Cleanup
-    disable_raw_mode()?;
-    terminal.show_cursor()?;
-    
-    res
+    let res = run(&mut terminal, settings, &signals);
+
+    // Cleanup, reporting a failure of the event loop first
+    let restored = tui::restore();
+    res?;
+    restored
 }
 
+/// Render frames without touching the terminal mode, to stdout or an export file.
+fn dump(config: &Config, headless: Headless) -> Result<()> {
+    let first = config.seed.unwrap_or(0);
+    let size = (headless.width, headless.height);
+
+    let (path, write): (_, Export) = match headless.target {
+        Target::Stdout(format) => {
+            let mut stdout = io::stdout().lock();
+            let frames = headless.frames.unwrap_or(1);
+            let buffers = headless::render_frames(config, first, frames, size.0, size.1);
+            for (index, buffer) in buffers.enumerate() {
+                if index > 0 {
+                    writeln!(stdout)?;
+                }
+                stdout.write_all(format.encode(&buffer).as_bytes())?;
+            }
+            return Ok(());
+        }
+        Target::Gif(path) => (path, export::gif::write_gif),
+        Target::Cast(path) => (path, export::cast::write_cast),
+        Target::Svg(path) => (path, export::svg::write_svg),
+    };
+
+    let frames = headless.frames.unwrap_or_else(|| config.cycle_frames());
+    let file =
+        File::create(&path).wrap_err_with(|| format!("failed to create {}", path.display()))?;
+    write(config, first, frames, size, BufWriter::new(file))
+        .wrap_err_with(|| format!("failed to write {}", path.display()))
+}
+
+/// Signature shared by the file exporters.
+type Export = fn(&Config, u64, u64, (u16, u16), BufWriter<File>) -> Result<()>;
+
 /// Main event loop with animation timing and input handling.
-fn run(terminal: &mut Terminal<CrosstermBackend<&mut io::Stdout>>) -> Result<()> {
-    let mut tick: u64 = 0;
-    let mut last_tick = Instant::now();
-    const TICK_RATE: Duration = Duration::from_millis(80); // ~12.5 FPS
+///
+/// Frames are drawn at the configured rate, but what they show comes from the
+/// animation clock, so a slow redraw never slows the animation down. Nothing is
+/// redrawn unless the frame changed: while paused the loop only wakes up to
+/// check for input, signals and config edits.
+fn run(terminal: &mut Tui, mut settings: Settings, signals: &Signals) -> Result<()> {
+    // How long to sleep when nothing is animating
+    const IDLE_POLL: Duration = Duration::from_millis(250);
+
+    let seed = settings.config.seed.unwrap_or(0);
+    let mut clock = Clock::new(seed as f64 / settings.config.fps);
+    let mut playlist = Playlist::new(&settings.config);
+    let mut view = View::default();
+    let mut drag = None;
+    let mut mouse_captured = false;
+    let mut countdown_second = None;
+    let mut saver = None;
+    let mut last_input = Instant::now();
+    let mut last_frame = Instant::now();
+    let listener = settings
+        .config
+        .listen
+        .as_ref()
+        .map(Listener::bind)
+        .transpose()?;
+    let mut board = Board::new();
+    let address = settings.config.sync_address;
+    let (mut leader, mut follower) = match settings.config.sync {
+        Some(Role::Lead) => (Some(Leader::bind(address)?), None),
+        Some(Role::Follow) => (None, Some(Follower::connect(address)?)),
+        None => (None, None),
+    };
+    let started = Instant::now();
+    let mut next_frame = Instant::now();
+    let mut dirty = true;
 
     loop {
-        // Draw current frame
-        terminal.draw(|f| draw_ui(f, tick))?;
+        dirty |= settings.reload();
+        let config = &mut settings.config;
+        if config.mouse != mouse_captured {
+            tui::capture_mouse(config.mouse)?;
+            mouse_captured = config.mouse;
+        }
+
+        // Stop once the requested run time is over or we were asked to quit
+        if signals.received() || config.duration.is_some_and(|d| started.elapsed() >= d) {
+            break;
+        }
+        let tick_rate = config.tick_rate();
+
+        // Wander off as a screensaver when asked to or left alone long enough
+        let idle = config
+            .screensaver_after
+            .is_some_and(|after| last_input.elapsed() >= after);
+        if saver.is_none() && (config.screensaver || idle) {
+            saver = Some(Screensaver::new(seed));
+            drag = None;
+        }
+
+        // A countdown follows the wall clock, even while the animation is paused
+        if config.counts_down() {
+            let second = countdown::now().and_utc().timestamp();
+            dirty |= countdown_second.replace(second) != Some(second);
+        }
+
+        // Advance animation time and step the particles by the same amount
+        let now = Instant::now();
+        if now >= next_frame {
+            next_frame = now + tick_rate;
+            let delta = clock.update_at(now);
+            let wall = (now - last_frame).as_secs_f64();
+            last_frame = now;
+
+            // Keep in phase with the other hearts; catching up is a seek, so
+            // the particles only move on by the time that really passed
+            if let Some(follower) = &mut follower {
+                dirty |= follower.sync(&mut clock, now) != 0.0;
+                // Show the leader's scene and view, which only it changes
+                if let Some(beat) = follower.beat(now) {
+                    dirty |= beat.scenes != playlist.offset() || beat.view != view;
+                    playlist.set_offset(beat.scenes);
+                    view = beat.view;
+                }
+            }
+            if let Some(leader) = &mut leader {
+                leader.broadcast(&Beat::new(&clock, config, &playlist, &view), now);
+            }
+
+            // The scene on screen may draw on a narrower canvas, e.g. beside
+            // a countdown
+            let size = terminal.size()?;
+            let area = Rect::new(0, 0, size.width, size.height);
+            let active = playlist.config(config, clock.seconds());
+            if let Some(saver) = &mut saver {
+                saver.update(area, &active, wall);
+                dirty = true;
+            }
+            let shown = placement(view, saver.as_ref(), area, &active);
 
-        // Calculate poll timeout for smooth timing
-        let timeout = TICK_RATE
-            .checked_sub(last_tick.elapsed())
-            .unwrap_or_default();
+            // Put up message board notes as their turn comes
+            if let Some(listener) = &listener {
+                listener.deliver(&mut board);
+            }
+            let showing = !board.is_idle();
+            if let Some(note) = board.update(wall, config.note_duration) {
+                let at = shown.offset;
+                if let Some(particles) = playlist.particles(clock.seconds()) {
+                    match note.effect {
+                        NoteEffect::Burst => particles.burst(config, at),
+                        NoteEffect::Confetti => particles
+                            .confetti(config, world_bounds(canvas_area(area, &active), &active)),
+                        _ => {}
+                    }
+                }
+            }
+            dirty |= showing || !board.is_idle();
+            if delta != 0.0 {
+                playlist.update(config, &shown, clock.seconds(), delta.abs());
+                dirty = true;
+            }
+        }
+
+        // Draw only frames that differ from what is on screen
+        if dirty {
+            let waiting = follower
+                .as_ref()
+                .is_some_and(|f| !f.is_locked(Instant::now()));
+            terminal.draw(|f| {
+                let shown = placement(view, saver.as_ref(), f.area(), config);
+                playlist.render(f, config, &shown, clock.seconds());
+                if let Some(saver) = &saver {
+                    saver.finish(f.buffer_mut(), config);
+                }
+                if let Some((note, shown)) = board.current() {
+                    draw_note(f, note, config.color(clock.seconds()), shown, config);
+                }
+                let status = clock
+                    .status()
+                    .or_else(|| waiting.then(|| String::from("waiting for leader")));
+                if let Some(status) = status {
+                    draw_status(f, &status);
+                }
+                if let Some(err) = &settings.error {
+                    draw_error(f, err);
+                }
+            })?;
+            dirty = false;
+        }
+
+        // Sleep until the next frame is due, or a while longer when paused
+        let timeout = if clock.is_paused() && saver.is_none() && board.is_idle() {
+            IDLE_POLL
+        } else {
+            next_frame.saturating_duration_since(Instant::now())
+        };
 
         // Handle input events
-        if event::poll(timeout)? {
-            if let Event::Key(key) = event::read()? {
-                if matches!(key.code, KeyCode::Char('q') | KeyCode::Esc) {
+        if !event::poll(timeout)? {
+            continue;
+        }
+        let event = event::read()?;
+        if matches!(event, Event::Key(_) | Event::Mouse(_)) {
+            last_input = Instant::now();
+            // Any input ends the screensaver, and quits when started as one
+            if saver.take().is_some() {
+                if config.screensaver {
                     break;
                 }
+                dirty = true;
+                continue;
             }
         }
-
-        // Advance animation frame
-        if last_tick.elapsed() >= TICK_RATE {
-            tick = tick.wrapping_add(1);
-            last_tick = Instant::now();
+        match event {
+            Event::Key(key) if key.kind == KeyEventKind::Press => {
+                match key.code {
+                    KeyCode::Char('q') | KeyCode::Esc => break,
+                    KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => break,
+                    KeyCode::Char('s') => config.cycle_shape(),
+                    KeyCode::Char('f') => config.filled = !config.filled,
+                    KeyCode::Char('3') => config.solid = !config.solid,
+                    KeyCode::Char('m') => config.marker = config.marker.next(),
+                    KeyCode::Char(' ') => clock.toggle_pause(),
+                    KeyCode::Char('+' | '=') => clock.faster(),
+                    KeyCode::Char('-') => clock.slower(),
+                    KeyCode::Char('r') => clock.reverse(),
+                    // Followers take the view and scene from the leader
+                    KeyCode::Char('0' | 'n' | 'p') if follower.is_some() => {}
+                    KeyCode::Char('0') => view = View::default(),
+                    KeyCode::Char('n') => playlist.next(clock.seconds()),
+                    KeyCode::Char('p') => playlist.previous(clock.seconds()),
+                    KeyCode::Char('.') => {
+                        let delta = clock.step(tick_rate);
+                        let size = terminal.size()?;
+                        let area = Rect::new(0, 0, size.width, size.height);
+                        let active = playlist.config(config, clock.seconds());
+                        let shown = placement(view, saver.as_ref(), area, &active);
+                        playlist.update(config, &shown, clock.seconds(), delta.abs());
+                    }
+                    _ => {
+                        let size = terminal.size()?;
+                        let area = Rect::new(0, 0, size.width, size.height);
+                        let active = playlist.config(config, clock.seconds());
+                        let bounds = world_bounds(canvas_area(area, &active), &active);
+                        if let Some(particles) = playlist.particles(clock.seconds()) {
+                            particles.confetti(config, bounds);
+                        }
+                    }
+                }
+                dirty = true;
+            }
+            Event::Mouse(mouse) if config.mouse && follower.is_none() => {
+                let size = terminal.size()?;
+                let area = Rect::new(0, 0, size.width, size.height);
+                let active = playlist.config(config, clock.seconds());
+                let particles = playlist.particles(clock.seconds());
+                dirty |= handle_mouse(mouse, area, &active, &mut view, particles, &mut drag);
+            }
+            Event::Resize(..) => dirty = true,
+            _ => {}
         }
     }
-    
+
     Ok(())
 }
 
-/// Render the main UI with animated rainbow heart canvas.
-fn draw_ui(frame: &mut Frame, tick: u64) {
-    let area = frame.area();
-    let color = rainbow_color(tick);
-
-    let canvas = Canvas::default()
-        .x_bounds([-2.0, 2.0])
-        .y_bounds([-2.0, 2.0])
-        .paint(|ctx| {
-            // Draw thick heart (0.05 world units thickness)
-            draw_heart(ctx, color, 0.05);
-        });
-
-    frame.render_widget(canvas, area);
+/// A mouse button held down over the canvas.
+struct Drag {
+    button: MouseButton,
+    /// World point under the mouse at the last event, across the whole grid
+    /// on a tile.
+    last: Point,
+    /// Whether the mouse moved since the button went down.
+    moved: bool,
 }
 
-/// Generate rainbow color from tick counter using predefined bright colors.
-fn rainbow_color(tick: u64) -> Color {
-    const RAINBOW: [Color; 5] = [
-        Color::Red,
-        Color::Yellow,
-        Color::Magenta,
-        Color::LightRed,
-        Color::LightMagenta,
-    ];
-
-    RAINBOW[(tick as usize) % RAINBOW.len()]
+/// Where the heart is drawn in `area`: wandering as a screensaver or where
+/// the user put it, then moved onto this terminal's tile of a shared heart.
+fn placement(view: View, saver: Option<&Screensaver>, area: Rect, config: &Config) -> View {
+    let view = saver.map_or(view, Screensaver::view);
+    match config.tile {
+        Some(tile) => tile.place(&view, area, config),
+        None => view,
+    }
 }
 
-/// Draw thick Valentine heart using parametric equation with multiple layers.
-fn draw_heart(ctx: &mut Context, color: Color, thickness: f64) {
-    const STEPS: usize = 1000;
-    const LAYERS: usize = 4;
-
-    for layer in 0..LAYERS {
-        // Scale each layer outward for thickness effect
-        let scale = 1.0 + (layer as f64) * thickness;
-        let mut points = Vec::with_capacity(STEPS + 1);
-
-        for i in 0..=STEPS {
-            let t = (i as f64 / STEPS as f64) * std::f64::consts::TAU;
-
-            // Classic heart parametric equation
-            let x = 16.0 * t.sin().powi(3);
-            let y = 13.0 * t.cos()
-                - 5.0 * (2.0 * t).cos()
-                - 2.0 * (3.0 * t).cos()
-                - (4.0 * t).cos();
-
-            // Normalize to [-2, 2] bounds and apply thickness scaling
-            points.push((
-                (x / 10.0) * scale,
-                (y / 10.0) * scale,
-            ));
-        }
+/// Apply a mouse event over a terminal of size `area` showing a scene drawn
+/// with `config`, returning whether the frame changed.
+///
+/// A left click releases a heart where it landed. Dragging with the left
+/// button moves the heart and with any other button turns it about its
+/// centre; the wheel zooms. On a tile the view is moved across the whole
+/// grid, so the heart keeps up with the mouse.
+fn handle_mouse(
+    mouse: MouseEvent,
+    area: Rect,
+    config: &Config,
+    view: &mut View,
+    particles: Option<&mut Particles>,
+    drag: &mut Option<Drag>,
+) -> bool {
+    // Zoom factor per wheel notch
+    const ZOOM_STEP: f64 = 1.1;
 
-        ctx.draw(&Points {
-            coords: &points,
-            color,
-        });
+    let local = cell_to_world(area, config, mouse.column, mouse.row);
+    let at = match config.tile {
+        Some(tile) => local.map(|at| tile.to_grid(at, area, config)),
+        None => local,
+    };
+    match mouse.kind {
+        MouseEventKind::Down(button) => {
+            *drag = at.map(|last| Drag {
+                button,
+                last,
+                moved: false,
+            });
+            false
+        }
+        MouseEventKind::Drag(_) => {
+            let (Some(drag), Some(at)) = (drag.as_mut(), at) else {
+                return false;
+            };
+            match drag.button {
+                MouseButton::Left => view.translate(drag.last, at),
+                _ => view.rotate(drag.last, at),
+            }
+            drag.last = at;
+            drag.moved = true;
+            true
+        }
+        MouseEventKind::Up(_) => match drag.take() {
+            Some(drag) if drag.button == MouseButton::Left && !drag.moved => {
+                let (Some(particles), Some(local)) = (particles, local) else {
+                    return false;
+                };
+                particles.burst(config, local);
+                true
+            }
+            _ => false,
+        },
+        MouseEventKind::ScrollUp => {
+            view.zoom_by(ZOOM_STEP);
+            true
+        }
+        MouseEventKind::ScrollDown => {
+            view.zoom_by(1.0 / ZOOM_STEP);
+            true
+        }
+        _ => false,
     }
 }
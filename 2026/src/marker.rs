@@ -0,0 +1,57 @@
+// src/marker.rs
+//! Symbols the canvas draws its dots with, and how many dots each fits per cell.
+
+use clap::ValueEnum;
+use ratatui::symbols;
+use serde::Deserialize;
+
+/// Canvas marker selectable with `--marker`, `marker = "..."` or the `m` key.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Marker {
+    /// Braille patterns, 2x4 dots per cell.
+    #[default]
+    Braille,
+    /// Upper and lower half blocks, 1x2 square pixels per cell.
+    HalfBlock,
+    /// Quadrant blocks, 2x2 pixels per cell.
+    Quadrant,
+    /// Sextant blocks, 2x3 pixels per cell; needs a font with Legacy Computing symbols.
+    Sextant,
+    /// One `•` per cell.
+    Dot,
+    /// One `▄` per cell.
+    Bar,
+}
+
+impl Marker {
+    /// The next marker in cycling order, wrapping around at the end.
+    pub fn next(self) -> Self {
+        let all = Self::value_variants();
+        let index = all.iter().position(|&marker| marker == self).unwrap_or(0);
+        all[(index + 1) % all.len()]
+    }
+
+    /// The ratatui marker drawing this style.
+    pub fn symbol(self) -> symbols::Marker {
+        match self {
+            Marker::Braille => symbols::Marker::Braille,
+            Marker::HalfBlock => symbols::Marker::HalfBlock,
+            Marker::Quadrant => symbols::Marker::Quadrant,
+            Marker::Sextant => symbols::Marker::Sextant,
+            Marker::Dot => symbols::Marker::Dot,
+            Marker::Bar => symbols::Marker::Bar,
+        }
+    }
+
+    /// Dots per cell as `(columns, rows)`.
+    pub fn resolution(self) -> (usize, usize) {
+        match self {
+            Marker::Braille => (2, 4),
+            Marker::HalfBlock => (1, 2),
+            Marker::Quadrant => (2, 2),
+            Marker::Sextant => (2, 3),
+            Marker::Dot | Marker::Bar => (1, 1),
+        }
+    }
+}
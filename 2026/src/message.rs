@@ -0,0 +1,127 @@
+// src/message.rs
+//! Message text shown with the heart: placement, big letters and reveal effects.
+//!
+//! Everything here is a pure function of the text and the animation time;
+//! [`render`](crate::render) lays the result out as a `Paragraph`.
+
+use clap::ValueEnum;
+use font8x8::{BASIC_FONTS, LATIN_FONTS, UnicodeFonts};
+use serde::Deserialize;
+
+/// Where the message goes relative to the heart.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Position {
+    /// On its own rows under the canvas.
+    #[default]
+    Below,
+    /// Over the middle of the canvas.
+    Inside,
+    /// In a column right of the canvas, or below it when there is no room.
+    Beside,
+}
+
+/// Lettering of the message.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Font {
+    /// One terminal cell per character.
+    #[default]
+    Plain,
+    /// FIGlet-style letters 8 cells wide and 4 rows tall, made of half blocks.
+    ///
+    /// Falls back to plain text when the message does not fit the width.
+    Big,
+}
+
+/// How the message appears over time.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Effect {
+    /// Shown in full from the start.
+    #[default]
+    None,
+    /// Typed out one character at a time.
+    Typewriter,
+    /// Faded in from the background.
+    FadeIn,
+    /// Scrolled from right to left across the screen, over and over.
+    Marquee,
+}
+
+/// Width and height of one big letter in cells.
+pub const BIG_SIZE: (usize, usize) = (8, 4);
+/// Seconds the fade-in effect takes.
+pub const FADE_SECONDS: f64 = 2.0;
+
+/// The rows of `text` in `font`.
+pub fn rows(text: &str, font: Font) -> Vec<String> {
+    match font {
+        Font::Plain => vec![text.to_owned()],
+        Font::Big => big_rows(text),
+    }
+}
+
+/// Width of `text` in `font`, in cells.
+pub fn width(text: &str, font: Font) -> usize {
+    let chars = text.chars().count();
+    match font {
+        Font::Plain => chars,
+        Font::Big => chars * BIG_SIZE.0,
+    }
+}
+
+/// The start of `text` typed after `seconds` at `speed` characters per second.
+pub fn typed(text: &str, seconds: f64, speed: f64) -> &str {
+    let shown = (seconds.max(0.0) * speed).floor() as usize;
+    match text.char_indices().nth(shown) {
+        Some((end, _)) => &text[..end],
+        None => text,
+    }
+}
+
+/// Opacity of the fade-in effect after `seconds`, from `0` to `1`.
+pub fn fade_in(seconds: f64) -> f64 {
+    (seconds / FADE_SECONDS).clamp(0.0, 1.0)
+}
+
+/// Columns the marquee has scrolled after `seconds` at `speed` cells per
+/// second, wrapping around once text of `width` cells has left a screen of
+/// `span` cells.
+pub fn marquee_offset(seconds: f64, speed: f64, width: usize, span: usize) -> usize {
+    let cycle = (width + span).max(1);
+    (seconds.max(0.0) * speed).floor() as usize % cycle
+}
+
+/// Render `text` in big letters, two glyph rows per half-block row.
+fn big_rows(text: &str) -> Vec<String> {
+    let glyphs: Vec<[u8; 8]> = text
+        .chars()
+        .map(|c| {
+            BASIC_FONTS
+                .get(c)
+                .or_else(|| LATIN_FONTS.get(c))
+                .or_else(|| BASIC_FONTS.get('?'))
+                .unwrap_or_default()
+        })
+        .collect();
+
+    (0..BIG_SIZE.1)
+        .map(|row| {
+            glyphs
+                .iter()
+                .flat_map(|glyph| {
+                    let (top, bottom) = (glyph[row * 2], glyph[row * 2 + 1]);
+                    (0..BIG_SIZE.0).map(move |col| {
+                        match (top & (1 << col) != 0, bottom & (1 << col) != 0) {
+                            (true, true) => '█',
+                            (true, false) => '▀',
+                            (false, true) => '▄',
+                            (false, false) => ' ',
+                        }
+                    })
+                })
+                .collect()
+        })
+        .collect()
+}
@@ -0,0 +1,407 @@
+// src/palette.rs
+//! Smooth color palettes, interpolation and terminal color-depth fallback.
+
+use std::{env, f64::consts::TAU};
+
+use clap::ValueEnum;
+use ratatui::style::Color;
+use serde::Deserialize;
+
+/// Built-in palettes selectable with `--palette` or `palette = "..."`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Palette {
+    /// Bright reds, yellows and magentas (the original look).
+    #[default]
+    Rainbow,
+    /// Deep reds and roses.
+    Rose,
+    /// Warm evening golds, oranges and purples.
+    Sunset,
+    /// Soft candy colors.
+    Pastel,
+    /// Shades of a single pink.
+    Pink,
+}
+
+impl Palette {
+    /// Color stops of the palette in cycling order.
+    pub fn stops(self) -> &'static [(u8, u8, u8)] {
+        match self {
+            Palette::Rainbow => &[
+                (255, 0, 0),
+                (255, 215, 0),
+                (255, 0, 255),
+                (255, 95, 95),
+                (255, 135, 255),
+            ],
+            Palette::Rose => &[(255, 95, 135), (215, 0, 95), (255, 135, 175), (175, 0, 55)],
+            Palette::Sunset => &[
+                (255, 215, 0),
+                (255, 140, 0),
+                (255, 69, 0),
+                (199, 21, 133),
+                (139, 0, 139),
+            ],
+            Palette::Pastel => &[
+                (255, 179, 186),
+                (255, 223, 186),
+                (255, 255, 186),
+                (186, 255, 201),
+                (186, 225, 255),
+                (224, 187, 255),
+            ],
+            Palette::Pink => &[
+                (255, 192, 203),
+                (255, 105, 180),
+                (255, 20, 147),
+                (199, 21, 133),
+            ],
+        }
+    }
+}
+
+/// Color space used to blend between palette stops.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Interpolation {
+    /// Hue, saturation and value; vivid but uneven in brightness.
+    Hsv,
+    /// Perceptual lightness, chroma and hue; even brightness throughout.
+    #[default]
+    Oklch,
+}
+
+/// A cyclic gradient through a list of color stops.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Gradient {
+    stops: Vec<[f64; 3]>,
+    interpolation: Interpolation,
+}
+
+impl Gradient {
+    /// Build a gradient through `stops`, converting them to the blending space once.
+    pub fn new(stops: &[(u8, u8, u8)], interpolation: Interpolation) -> Self {
+        let stops = stops
+            .iter()
+            .map(|&rgb| match interpolation {
+                Interpolation::Hsv => rgb_to_hsv(rgb),
+                Interpolation::Oklch => rgb_to_oklch(rgb),
+            })
+            .collect();
+        Self {
+            stops,
+            interpolation,
+        }
+    }
+
+    /// Color at position `t`, where every whole number is back at the first stop.
+    pub fn sample(&self, t: f64) -> Color {
+        let n = self.stops.len();
+        if n == 0 {
+            return Color::Reset;
+        }
+        let pos = t.rem_euclid(1.0) * n as f64;
+        let index = (pos.floor() as usize).min(n - 1);
+        let (from, to) = (self.stops[index], self.stops[(index + 1) % n]);
+        let f = pos - index as f64;
+
+        // Channel 0 is the hue for HSV and channel 2 for OKLCH
+        let mixed = match self.interpolation {
+            Interpolation::Hsv => [
+                mix_hue(from[0], to[0], f),
+                mix(from[1], to[1], f),
+                mix(from[2], to[2], f),
+            ],
+            Interpolation::Oklch => [
+                mix(from[0], to[0], f),
+                mix(from[1], to[1], f),
+                mix_hue(from[2], to[2], f),
+            ],
+        };
+        let (r, g, b) = match self.interpolation {
+            Interpolation::Hsv => hsv_to_rgb(mixed),
+            Interpolation::Oklch => oklch_to_rgb(mixed),
+        };
+        Color::Rgb(r, g, b)
+    }
+}
+
+/// How many colors the terminal can show.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ColorDepth {
+    /// Detect from `COLORTERM` and `TERM`.
+    #[default]
+    Auto,
+    /// 24-bit RGB.
+    #[value(name = "truecolor")]
+    #[serde(rename = "truecolor")]
+    TrueColor,
+    /// xterm 256-color palette.
+    #[value(name = "256")]
+    #[serde(rename = "256")]
+    Ansi256,
+    /// The 16 basic ANSI colors.
+    #[value(name = "16")]
+    #[serde(rename = "16")]
+    Ansi16,
+}
+
+impl ColorDepth {
+    /// Replace `Auto` with the depth advertised by the environment.
+    pub fn resolve(self) -> Self {
+        if self != ColorDepth::Auto {
+            return self;
+        }
+        let colorterm = env::var("COLORTERM").unwrap_or_default();
+        let term = env::var("TERM").unwrap_or_default();
+        Self::detect(&colorterm, &term)
+    }
+
+    /// The depth advertised by the given `COLORTERM` and `TERM` values.
+    pub fn detect(colorterm: &str, term: &str) -> Self {
+        let colorterm = colorterm.to_lowercase();
+        let term = term.to_lowercase();
+        if colorterm.contains("truecolor") || colorterm.contains("24bit") || term.contains("direct")
+        {
+            ColorDepth::TrueColor
+        } else if term.contains("256") {
+            ColorDepth::Ansi256
+        } else {
+            ColorDepth::Ansi16
+        }
+    }
+
+    /// Convert `color` to the nearest color this depth can show.
+    ///
+    /// Named and indexed colors are left alone unless they exceed the depth.
+    /// `Auto` leaves every color alone, so [`resolve`](Self::resolve) it once
+    /// up front rather than per color.
+    pub fn quantize(self, color: Color) -> Color {
+        match (self, color) {
+            (ColorDepth::Auto | ColorDepth::TrueColor, _) => color,
+            (ColorDepth::Ansi256, Color::Rgb(r, g, b)) => Color::Indexed(nearest_256((r, g, b))),
+            (ColorDepth::Ansi256, _) => color,
+            (ColorDepth::Ansi16, Color::Rgb(..) | Color::Indexed(16..)) => nearest_16(rgb(color)),
+            (ColorDepth::Ansi16, _) => color,
+        }
+    }
+}
+
+/// The closest entry of the xterm 6x6x6 color cube or gray ramp.
+fn nearest_256((r, g, b): (u8, u8, u8)) -> u8 {
+    // Map a channel to the nearest of the cube levels 0, 95, 135, 175, 215, 255
+    let level = |c: u8| {
+        if c < 48 {
+            0
+        } else if c < 115 {
+            1
+        } else {
+            (c - 35) / 40
+        }
+    };
+    let cube = 16 + 36 * level(r) + 6 * level(g) + level(b);
+
+    let mean = (u16::from(r) + u16::from(g) + u16::from(b)) / 3;
+    let gray = if mean > 238 {
+        255
+    } else {
+        232 + (mean.saturating_sub(3) / 10) as u8
+    };
+
+    [cube, gray]
+        .into_iter()
+        .min_by_key(|&index| distance(rgb(Color::Indexed(index)), (r, g, b)))
+        .unwrap_or(cube)
+}
+
+/// The closest of the 16 basic ANSI colors.
+fn nearest_16(target: (u8, u8, u8)) -> Color {
+    BASIC
+        .into_iter()
+        .min_by_key(|&color| distance(rgb(color), target))
+        .unwrap_or(Color::Reset)
+}
+
+/// Squared RGB distance, weighted towards green like the eye.
+fn distance((r0, g0, b0): (u8, u8, u8), (r1, g1, b1): (u8, u8, u8)) -> u32 {
+    let d = |a: u8, b: u8| u32::from(a.abs_diff(b)).pow(2);
+    2 * d(r0, r1) + 4 * d(g0, g1) + 3 * d(b0, b1)
+}
+
+fn mix(a: f64, b: f64, t: f64) -> f64 {
+    a + (b - a) * t
+}
+
+/// Blend two hues in radians along the shorter way round the circle.
+fn mix_hue(a: f64, b: f64, t: f64) -> f64 {
+    let delta = (b - a + TAU / 2.0).rem_euclid(TAU) - TAU / 2.0;
+    (a + delta * t).rem_euclid(TAU)
+}
+
+/// Hue in radians, saturation and value in `[0, 1]`.
+fn rgb_to_hsv((r, g, b): (u8, u8, u8)) -> [f64; 3] {
+    let [r, g, b] = [r, g, b].map(|c| f64::from(c) / 255.0);
+    let max = r.max(g).max(b);
+    let delta = max - r.min(g).min(b);
+    let hue = if delta == 0.0 {
+        0.0
+    } else if max == r {
+        ((g - b) / delta).rem_euclid(6.0)
+    } else if max == g {
+        (b - r) / delta + 2.0
+    } else {
+        (r - g) / delta + 4.0
+    };
+    let saturation = if max == 0.0 { 0.0 } else { delta / max };
+    [hue * TAU / 6.0, saturation, max]
+}
+
+fn hsv_to_rgb([hue, saturation, value]: [f64; 3]) -> (u8, u8, u8) {
+    let h = hue.rem_euclid(TAU) / TAU * 6.0;
+    let c = value * saturation;
+    let x = c * (1.0 - (h.rem_euclid(2.0) - 1.0).abs());
+    let (r, g, b) = match h as u8 {
+        0 => (c, x, 0.0),
+        1 => (x, c, 0.0),
+        2 => (0.0, c, x),
+        3 => (0.0, x, c),
+        4 => (x, 0.0, c),
+        _ => (c, 0.0, x),
+    };
+    let m = value - c;
+    (to_byte(r + m), to_byte(g + m), to_byte(b + m))
+}
+
+/// OKLab lightness, chroma and hue in radians.
+fn rgb_to_oklch((r, g, b): (u8, u8, u8)) -> [f64; 3] {
+    let [r, g, b] = [r, g, b].map(|c| to_linear(f64::from(c) / 255.0));
+
+    let l = (0.412_221_470_8 * r + 0.536_332_536_3 * g + 0.051_445_992_9 * b).cbrt();
+    let m = (0.211_903_498_2 * r + 0.680_699_545_1 * g + 0.107_396_956_6 * b).cbrt();
+    let s = (0.088_302_461_9 * r + 0.281_718_837_6 * g + 0.629_978_700_5 * b).cbrt();
+
+    let lightness = 0.210_454_255_3 * l + 0.793_617_785_0 * m - 0.004_072_046_8 * s;
+    let a = 1.977_998_495_1 * l - 2.428_592_205_0 * m + 0.450_593_709_9 * s;
+    let b = 0.025_904_037_1 * l + 0.782_771_766_2 * m - 0.808_675_766_0 * s;
+    [lightness, a.hypot(b), b.atan2(a)]
+}
+
+fn oklch_to_rgb([lightness, chroma, hue]: [f64; 3]) -> (u8, u8, u8) {
+    let (a, b) = (chroma * hue.cos(), chroma * hue.sin());
+
+    let l = (lightness + 0.396_337_777_4 * a + 0.215_803_757_3 * b).powi(3);
+    let m = (lightness - 0.105_561_345_8 * a - 0.063_854_172_8 * b).powi(3);
+    let s = (lightness - 0.089_484_177_5 * a - 1.291_485_548_0 * b).powi(3);
+
+    let r = 4.076_741_662_1 * l - 3.307_711_591_3 * m + 0.230_969_929_2 * s;
+    let g = -1.268_438_004_6 * l + 2.609_757_401_1 * m - 0.341_319_396_5 * s;
+    let b = -0.004_196_086_3 * l - 0.703_418_614_7 * m + 1.707_614_701_0 * s;
+    (
+        to_byte(from_linear(r)),
+        to_byte(from_linear(g)),
+        to_byte(from_linear(b)),
+    )
+}
+
+/// sRGB transfer function, encoded to linear light.
+fn to_linear(c: f64) -> f64 {
+    if c <= 0.040_45 {
+        c / 12.92
+    } else {
+        ((c + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+/// sRGB transfer function, linear light to encoded.
+fn from_linear(c: f64) -> f64 {
+    if c <= 0.003_130_8 {
+        12.92 * c
+    } else {
+        1.055 * c.powf(1.0 / 2.4) - 0.055
+    }
+}
+
+fn to_byte(c: f64) -> u8 {
+    (c.clamp(0.0, 1.0) * 255.0).round() as u8
+}
+
+/// The 16 basic ANSI colors in index order.
+const BASIC: [Color; 16] = [
+    Color::Black,
+    Color::Red,
+    Color::Green,
+    Color::Yellow,
+    Color::Blue,
+    Color::Magenta,
+    Color::Cyan,
+    Color::Gray,
+    Color::DarkGray,
+    Color::LightRed,
+    Color::LightGreen,
+    Color::LightYellow,
+    Color::LightBlue,
+    Color::LightMagenta,
+    Color::LightCyan,
+    Color::White,
+];
+
+/// Approximate RGB value of a color, using the xterm defaults for named colors.
+pub fn rgb(color: Color) -> (u8, u8, u8) {
+    match color {
+        Color::Rgb(r, g, b) => (r, g, b),
+        Color::Black => (0, 0, 0),
+        Color::Red => (205, 0, 0),
+        Color::Green => (0, 205, 0),
+        Color::Yellow => (205, 205, 0),
+        Color::Blue => (0, 0, 238),
+        Color::Magenta => (205, 0, 205),
+        Color::Cyan => (0, 205, 205),
+        Color::Gray => (229, 229, 229),
+        Color::DarkGray => (127, 127, 127),
+        Color::LightRed => (255, 0, 0),
+        Color::LightGreen => (0, 255, 0),
+        Color::LightYellow => (255, 255, 0),
+        Color::LightBlue => (92, 92, 255),
+        Color::LightMagenta => (255, 0, 255),
+        Color::LightCyan => (0, 255, 255),
+        Color::White | Color::Reset => (255, 255, 255),
+        Color::Indexed(index) => indexed_rgb(index),
+    }
+}
+
+/// RGB value of an xterm 256-color palette entry.
+fn indexed_rgb(index: u8) -> (u8, u8, u8) {
+    const LEVELS: [u8; 6] = [0, 95, 135, 175, 215, 255];
+    match index {
+        0..=15 => rgb(BASIC[usize::from(index)]),
+        16..=231 => {
+            let i = index - 16;
+            (
+                LEVELS[usize::from(i / 36)],
+                LEVELS[usize::from(i / 6 % 6)],
+                LEVELS[usize::from(i % 6)],
+            )
+        }
+        232..=255 => {
+            let level = 8 + 10 * (index - 232);
+            (level, level, level)
+        }
+    }
+}
+
+/// `color` with its hue turned by `angle` radians, keeping saturation and value.
+pub fn rotate_hue(color: Color, angle: f64) -> Color {
+    let [hue, saturation, value] = rgb_to_hsv(rgb(color));
+    let (r, g, b) = hsv_to_rgb([hue + angle, saturation, value]);
+    Color::Rgb(r, g, b)
+}
+
+/// Linear blend from `from` (at `t = 0`) to `to` (at `t = 1`) in RGB space.
+pub fn lerp(from: Color, to: Color, t: f64) -> Color {
+    let t = t.clamp(0.0, 1.0);
+    let ((r0, g0, b0), (r1, g1, b1)) = (rgb(from), rgb(to));
+    let mix = |a: u8, b: u8| (f64::from(a) + (f64::from(b) - f64::from(a)) * t).round() as u8;
+    Color::Rgb(mix(r0, r1), mix(g0, g1), mix(b0, b1))
+}
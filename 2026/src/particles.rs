@@ -0,0 +1,283 @@
+// src/particles.rs
+//! Small effects around the heart: rising mini-hearts, sparkles on each beat
+//! and confetti bursts.
+//!
+//! Particles live in canvas world coordinates. The app steps them once per
+//! tick with [`Particles::update`] and they are painted on the same canvas as
+//! the heart. Spawning and culling follow the canvas's world bounds, as given
+//! by [`crate::render::world_bounds`], so effects fill a wide terminal too.
+
+use ratatui::style::Color;
+
+use crate::{config::Config, shape::Point, view::View};
+
+/// What a particle looks like and how it moves.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Kind {
+    /// A `♥` drifting up from the bottom edge.
+    Heart,
+    /// A short-lived star thrown off the outline on a beat.
+    Sparkle,
+    /// A dot of confetti falling from the top edge.
+    Confetti,
+}
+
+impl Kind {
+    /// Vertical acceleration in world units per second squared.
+    fn gravity(self) -> f64 {
+        match self {
+            Kind::Heart => 0.05,
+            Kind::Sparkle => -0.6,
+            Kind::Confetti => -1.2,
+        }
+    }
+}
+
+/// One moving, fading particle.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Particle {
+    /// Look and physics.
+    pub kind: Kind,
+    /// Position in world coordinates.
+    pub position: Point,
+    /// Velocity in world units per second.
+    pub velocity: Point,
+    /// Seconds since it was spawned.
+    pub age: f64,
+    /// Seconds it lives for.
+    pub lifetime: f64,
+    /// Color at birth, faded towards black as it ages.
+    pub color: Color,
+}
+
+impl Particle {
+    /// Fraction of the lifetime left, from `1` at birth to `0`.
+    pub fn life(&self) -> f64 {
+        (1.0 - self.age / self.lifetime).clamp(0.0, 1.0)
+    }
+}
+
+/// Every live particle plus the state needed to spawn new ones.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Particles {
+    particles: Vec<Particle>,
+    rng: Rng,
+    /// Fractional rising hearts owed from previous updates.
+    pending_hearts: f64,
+    /// Index of the last beat that threw sparkles.
+    last_beat: Option<i64>,
+}
+
+impl Particles {
+    /// Points sampled along the outline for sparkles to start from.
+    const SPARKLE_SOURCES: usize = 256;
+
+    /// An empty system whose random choices follow `seed`.
+    pub fn new(seed: u64) -> Self {
+        Self {
+            particles: Vec::new(),
+            rng: Rng::new(seed),
+            pending_hearts: 0.0,
+            last_beat: None,
+        }
+    }
+
+    /// The live particles, oldest first.
+    pub fn iter(&self) -> impl Iterator<Item = &Particle> {
+        self.particles.iter()
+    }
+
+    /// Number of live particles.
+    pub fn len(&self) -> usize {
+        self.particles.len()
+    }
+
+    /// Whether no particle is alive.
+    pub fn is_empty(&self) -> bool {
+        self.particles.is_empty()
+    }
+
+    /// Advance every particle by `dt` seconds and spawn the ones due by
+    /// `seconds` of animation time, dropping those that left the canvas with
+    /// world bounds `(x_bounds, y_bounds)`.
+    ///
+    /// `dt` is how far the particles move, so it stays positive while the
+    /// animation plays backwards; beats still throw sparkles either way, from
+    /// the outline as `view` places it.
+    pub fn update(
+        &mut self,
+        config: &Config,
+        view: &View,
+        bounds: ([f64; 2], [f64; 2]),
+        seconds: f64,
+        dt: f64,
+    ) {
+        let (x_bounds, y_bounds) = bounds;
+        for particle in &mut self.particles {
+            particle.age += dt;
+            particle.velocity.1 += particle.kind.gravity() * dt;
+            particle.position.0 += particle.velocity.0 * dt;
+            particle.position.1 += particle.velocity.1 * dt;
+            if particle.kind == Kind::Heart {
+                // Sway from side to side on the way up
+                particle.position.0 += 0.15 * (particle.age * 2.0).cos() * dt;
+            }
+        }
+        // A little room beyond the edges, so nothing vanishes while in sight
+        let near = |value: f64, [low, high]: [f64; 2]| {
+            let margin = 0.1 * (high - low);
+            (low - margin..=high + margin).contains(&value)
+        };
+        self.particles.retain(|particle| {
+            particle.age < particle.lifetime
+                && near(particle.position.0, x_bounds)
+                && near(particle.position.1, y_bounds)
+        });
+
+        self.pending_hearts += config.heart_rate * dt;
+        while self.pending_hearts >= 1.0 {
+            self.pending_hearts -= 1.0;
+            self.spawn_heart(config, bounds);
+        }
+
+        let beat = (seconds * config.bpm / 60.0).floor() as i64;
+        if self.last_beat.is_some_and(|last| beat != last) {
+            self.spawn_sparkles(config, view, seconds);
+        }
+        self.last_beat = Some(beat);
+    }
+
+    /// Throw a burst of confetti from the top edge of a canvas with world
+    /// bounds `(x_bounds, y_bounds)`.
+    pub fn confetti(&mut self, config: &Config, (x_bounds, y_bounds): ([f64; 2], [f64; 2])) {
+        let gradient = config.gradient();
+        for _ in 0..config.confetti {
+            let particle = Particle {
+                kind: Kind::Confetti,
+                position: (self.rng.range(x_bounds[0], x_bounds[1]), y_bounds[1]),
+                velocity: (self.rng.range(-0.6, 0.6), self.rng.range(-0.4, 0.8)),
+                age: 0.0,
+                lifetime: self.rng.range(2.5, 4.0),
+                color: gradient.sample(self.rng.next_f64()),
+            };
+            self.push(particle, config);
+        }
+    }
+
+    /// Release a heart and a ring of sparkles at world point `at`, e.g. where
+    /// the mouse was clicked.
+    pub fn burst(&mut self, config: &Config, at: Point) {
+        let gradient = config.gradient();
+        let heart = Particle {
+            kind: Kind::Heart,
+            position: at,
+            velocity: (0.0, self.rng.range(0.3, 0.6)),
+            age: 0.0,
+            lifetime: self.rng.range(3.0, 6.0),
+            color: gradient.sample(self.rng.next_f64()),
+        };
+        self.push(heart, config);
+        for _ in 0..config.sparkles {
+            let angle = self.rng.range(0.0, std::f64::consts::TAU);
+            let speed = self.rng.range(0.5, 1.2);
+            let particle = Particle {
+                kind: Kind::Sparkle,
+                position: at,
+                velocity: (angle.cos() * speed, angle.sin() * speed),
+                age: 0.0,
+                lifetime: self.rng.range(0.4, 0.9),
+                color: gradient.sample(self.rng.next_f64()),
+            };
+            self.push(particle, config);
+        }
+    }
+
+    /// Release a heart from the bottom edge of a canvas with world bounds
+    /// `(x_bounds, y_bounds)`.
+    fn spawn_heart(&mut self, config: &Config, (x_bounds, y_bounds): ([f64; 2], [f64; 2])) {
+        let particle = Particle {
+            kind: Kind::Heart,
+            position: (self.rng.range(x_bounds[0], x_bounds[1]) * 0.9, y_bounds[0]),
+            velocity: (0.0, self.rng.range(0.3, 0.6)),
+            age: 0.0,
+            lifetime: self.rng.range(6.0, 12.0),
+            color: config.gradient().sample(self.rng.next_f64()),
+        };
+        self.push(particle, config);
+    }
+
+    /// Throw sparkles outward from random points of the outline as it is at
+    /// `seconds`, placed by `view`.
+    fn spawn_sparkles(&mut self, config: &Config, view: &View, seconds: f64) {
+        if config.sparkles == 0 {
+            return;
+        }
+        let beat = config.heartbeat().scale(seconds);
+        let gradient = config.gradient();
+        let outlines = config.shape().outlines(Self::SPARKLE_SOURCES);
+        let points: Vec<Point> = outlines.into_iter().flatten().collect();
+        if points.is_empty() {
+            return;
+        }
+        for _ in 0..config.sparkles {
+            let (x, y) = points[self.rng.below(points.len())];
+            let position = view.apply((x * beat, y * beat));
+            // Outward from the shape's centre, wherever the view put it
+            let (dx, dy) = (position.0 - view.offset.0, position.1 - view.offset.1);
+            let length = dx.hypot(dy).max(f64::EPSILON);
+            let speed = self.rng.range(0.5, 1.2);
+            let particle = Particle {
+                kind: Kind::Sparkle,
+                position,
+                velocity: (dx / length * speed, dy / length * speed),
+                age: 0.0,
+                lifetime: self.rng.range(0.4, 0.9),
+                color: gradient.sample(self.rng.next_f64()),
+            };
+            self.push(particle, config);
+        }
+    }
+
+    /// Keep `particle` unless the system is already at `max-particles`.
+    fn push(&mut self, particle: Particle, config: &Config) {
+        if self.particles.len() < config.max_particles {
+            self.particles.push(particle);
+        }
+    }
+}
+
+/// A small xorshift generator, so runs with the same seed look the same.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Rng(u64);
+
+impl Rng {
+    fn new(seed: u64) -> Self {
+        // Spread the seed bits with SplitMix64 and avoid the all-zero state
+        let mut z = seed.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        Self((z ^ (z >> 31)).max(1))
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+
+    /// Uniform in `[0, 1)`.
+    fn next_f64(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+
+    /// Uniform in `[low, high)`.
+    fn range(&mut self, low: f64, high: f64) -> f64 {
+        low + (high - low) * self.next_f64()
+    }
+
+    /// Uniform in `0..n`.
+    fn below(&mut self, n: usize) -> usize {
+        (self.next_f64() * n as f64) as usize % n.max(1)
+    }
+}
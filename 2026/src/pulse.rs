@@ -0,0 +1,104 @@
+// src/pulse.rs
+//! "Lub-dub" heartbeat that scales the shape over real time.
+
+use std::f64::consts::PI;
+
+use clap::ValueEnum;
+use serde::Deserialize;
+
+/// Curve used for the rise and fall of each beat.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Easing {
+    /// Constant speed.
+    Linear,
+    /// Gentle sine in-out.
+    #[default]
+    Sine,
+    /// Cubic in-out, snappier than sine.
+    Cubic,
+    /// Exponential in-out, a sharp thump.
+    Expo,
+}
+
+impl Easing {
+    /// Map `x` in `[0, 1]` onto the curve, keeping both end points fixed.
+    pub fn ease(self, x: f64) -> f64 {
+        let x = x.clamp(0.0, 1.0);
+        match self {
+            Easing::Linear => x,
+            Easing::Sine => 0.5 - 0.5 * (PI * x).cos(),
+            Easing::Cubic if x < 0.5 => 4.0 * x * x * x,
+            Easing::Cubic => 1.0 - (-2.0 * x + 2.0).powi(3) / 2.0,
+            Easing::Expo if x == 0.0 || x == 1.0 => x,
+            Easing::Expo if x < 0.5 => 2f64.powf(20.0 * x - 10.0) / 2.0,
+            Easing::Expo => (2.0 - 2f64.powf(-20.0 * x + 10.0)) / 2.0,
+        }
+    }
+}
+
+/// A two-part heartbeat: a strong "lub" followed shortly by a softer "dub".
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Heartbeat {
+    /// Beats per minute.
+    pub bpm: f64,
+    /// Extra scale at the peak of the "lub", e.g. `0.08` for 8% larger.
+    pub amplitude: f64,
+    /// Curve for each contraction.
+    pub easing: Easing,
+}
+
+impl Heartbeat {
+    /// The strong first contraction, timed in fractions of the beat period.
+    const LUB: Bump = Bump {
+        start: 0.0,
+        length: 0.25,
+        strength: 1.0,
+    };
+    /// The softer second contraction.
+    const DUB: Bump = Bump {
+        start: 0.3,
+        length: 0.3,
+        strength: 0.6,
+    };
+
+    /// Scale factor at `seconds` since the animation started.
+    pub fn scale(&self, seconds: f64) -> f64 {
+        if self.amplitude == 0.0 || self.bpm <= 0.0 {
+            return 1.0;
+        }
+        let phase = (seconds * self.bpm / 60.0).rem_euclid(1.0);
+        let envelope =
+            Self::LUB.envelope(phase, self.easing) + Self::DUB.envelope(phase, self.easing);
+        1.0 + self.amplitude * envelope
+    }
+}
+
+/// One contraction within a beat.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Bump {
+    /// Offset from the start of the beat.
+    start: f64,
+    /// Duration of the contraction.
+    length: f64,
+    /// Peak height relative to the full amplitude.
+    strength: f64,
+}
+
+impl Bump {
+    /// Fraction of the bump spent rising; the rest is the slower relaxation.
+    const ATTACK: f64 = 0.35;
+
+    fn envelope(self, phase: f64, easing: Easing) -> f64 {
+        let u = (phase - self.start) / self.length;
+        if !(0.0..1.0).contains(&u) {
+            return 0.0;
+        }
+        let level = if u < Self::ATTACK {
+            easing.ease(u / Self::ATTACK)
+        } else {
+            easing.ease(1.0 - (u - Self::ATTACK) / (1.0 - Self::ATTACK))
+        };
+        self.strength * level
+    }
+}
@@ -0,0 +1,608 @@
+// src/render.rs
+//! Drawing the heart scene into a ratatui buffer, plus the app's overlays.
+
+use std::f64::consts::TAU;
+
+use ratatui::{
+    Frame,
+    buffer::Buffer,
+    layout::{Constraint, Flex, Layout, Rect},
+    style::{Color, Stylize},
+    text::Line,
+    widgets::{
+        Block, Clear, Padding, Paragraph, Widget, Wrap,
+        canvas::{Canvas, Context, Points},
+    },
+};
+
+use crate::{
+    board::{Note, NoteEffect},
+    config::{Config, STEPS_RANGE},
+    fill::{self, FillStyle},
+    message::{self, Effect, Font, Position},
+    palette,
+    particles::{Kind, Particles},
+    shape::{Point, ShapeKind},
+    solid::{Dot, Mesh, Pose},
+    view::View,
+};
+
+/// Shape outlines kept between frames and rebuilt only when the settings that
+/// define them or the canvas resolution change, plus the solid heart's mesh
+/// once it is first needed.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Geometry {
+    key: Option<GeometryKey>,
+    steps: usize,
+    outlines: Vec<Vec<Point>>,
+    mesh: Option<Mesh>,
+}
+
+/// Everything the outlines depend on.
+#[derive(Debug, Clone, PartialEq)]
+struct GeometryKey {
+    shape: ShapeKind,
+    petals: u32,
+    points: Vec<Point>,
+    steps: Option<usize>,
+    /// Canvas dots per world unit, scaled by the outermost layer.
+    density: f64,
+}
+
+impl Geometry {
+    /// Samples per dot along the outline, enough to leave no gaps.
+    const SAMPLES_PER_DOT: f64 = 2.0;
+    /// Samples used to measure how long an outline is.
+    const PROBE_STEPS: usize = 256;
+
+    /// Resample the outlines if `config` describes a different shape or the
+    /// canvas now has `density` dots per world unit, returning whether they
+    /// were rebuilt.
+    ///
+    /// Unless `steps` is set, each outline gets about two samples per dot it
+    /// covers, so coarse markers do less work and fine ones have no gaps.
+    pub fn update(&mut self, config: &Config, density: f64) -> bool {
+        let outermost = 1.0 + f64::from(config.layers.saturating_sub(1)) * config.thickness;
+        let key = GeometryKey {
+            shape: config.shape,
+            petals: config.petals,
+            points: config.points.clone(),
+            steps: config.steps,
+            density: density * outermost,
+        };
+        if self.key.as_ref() == Some(&key) {
+            return false;
+        }
+
+        let shape = config.shape();
+        self.steps = config.steps.unwrap_or_else(|| {
+            let length = shape
+                .outlines(Self::PROBE_STEPS)
+                .iter()
+                .map(|outline| length(outline))
+                .fold(0.0, f64::max);
+            let steps = (length * key.density * Self::SAMPLES_PER_DOT).ceil() as usize;
+            steps.clamp(*STEPS_RANGE.start(), *STEPS_RANGE.end())
+        });
+        self.outlines = shape.outlines(self.steps);
+        self.key = Some(key);
+        true
+    }
+
+    /// Samples per outline in the cached geometry.
+    pub fn steps(&self) -> usize {
+        self.steps
+    }
+
+    /// The cached outlines in world coordinates, before the heartbeat scale.
+    pub fn outlines(&self) -> &[Vec<Point>] {
+        &self.outlines
+    }
+
+    /// The solid heart's mesh, built on first use.
+    pub fn mesh(&mut self) -> &Mesh {
+        // Enough triangles that none covers more than a few dots
+        const RINGS: usize = 48;
+        const SEGMENTS: usize = 96;
+        self.mesh
+            .get_or_insert_with(|| Mesh::heart(RINGS, SEGMENTS))
+    }
+}
+
+/// Length of the closed polygon `outline`.
+fn length(outline: &[Point]) -> f64 {
+    let Some(&last) = outline.last() else {
+        return 0.0;
+    };
+    let mut prev = last;
+    let mut total = 0.0;
+    for &point in outline {
+        total += (point.0 - prev.0).hypot(point.1 - prev.1);
+        prev = point;
+    }
+    total
+}
+
+/// Render the animated rainbow heart canvas, particles and optional message
+/// into `area` of `buf`; [`HeartWidget`](crate::widget::HeartWidget) is the
+/// public way in.
+///
+/// `seconds` is the animation time, which drives the palette, the heartbeat
+/// and the message effects. `view` places the heart on the canvas.
+///
+/// `geometry` is brought up to date with `config` and the canvas size first.
+pub(crate) fn draw_heart(
+    buf: &mut Buffer,
+    area: Rect,
+    config: &Config,
+    geometry: &mut Geometry,
+    view: &View,
+    particles: &Particles,
+    seconds: f64,
+) {
+    let color = config.color(seconds);
+    let text = config.message.as_deref().unwrap_or_default();
+    let (canvas_area, message_area, font) = layout(area, config);
+
+    draw_canvas(buf, canvas_area, config, geometry, view, particles, seconds);
+    if !text.is_empty() {
+        draw_message(buf, message_area, config, text, font, color, seconds);
+    }
+}
+
+/// The part of `area` the heart's canvas is drawn on, above or under the message.
+pub fn canvas_area(area: Rect, config: &Config) -> Rect {
+    layout(area, config).0
+}
+
+/// World coordinates of the centre of the terminal cell at `column`, `row`,
+/// or `None` outside the canvas.
+pub fn cell_to_world(area: Rect, config: &Config, column: u16, row: u16) -> Option<Point> {
+    let canvas = canvas_area(area, config);
+    if !canvas.contains((column, row).into()) {
+        return None;
+    }
+    let (x_bounds, y_bounds) = world_bounds(canvas, config);
+    let x = f64::from(column - canvas.x) + 0.5;
+    let y = f64::from(row - canvas.y) + 0.5;
+    Some((
+        x_bounds[0] + x / f64::from(canvas.width) * (x_bounds[1] - x_bounds[0]),
+        y_bounds[1] - y / f64::from(canvas.height) * (y_bounds[1] - y_bounds[0]),
+    ))
+}
+
+/// Split `area` into the canvas and message areas, and pick the font the
+/// message fits in.
+fn layout(area: Rect, config: &Config) -> (Rect, Rect, Font) {
+    let text = config.message.as_deref().unwrap_or_default();
+    // A message beside the canvas gets at most half the width
+    let room = match config.message_position {
+        Position::Beside => area.width / 2,
+        Position::Below | Position::Inside => area.width,
+    };
+
+    // Big letters only when they fit, unless they scroll by anyway
+    let font = match config.message_font {
+        Font::Big
+            if config.message_effect != Effect::Marquee
+                && message::width(text, Font::Big) > usize::from(room) =>
+        {
+            Font::Plain
+        }
+        font => font,
+    };
+    let height = match text {
+        "" => 0,
+        _ => message::rows(text, font).len() as u16,
+    };
+    // Text width plus a column of space on either side
+    let column = (message::width(text, font) + 2).min(usize::from(u16::MAX)) as u16;
+
+    match config.message_position {
+        Position::Beside if height > 0 && column <= room => {
+            let [canvas_area, column_area] =
+                Layout::horizontal([Constraint::Min(0), Constraint::Length(column)]).areas(area);
+            let [message_area] = Layout::vertical([Constraint::Length(height)])
+                .flex(Flex::Center)
+                .areas(column_area);
+            (canvas_area, message_area, font)
+        }
+        Position::Below | Position::Beside => {
+            let [canvas_area, message_area] =
+                Layout::vertical([Constraint::Min(0), Constraint::Length(height)]).areas(area);
+            (canvas_area, message_area, font)
+        }
+        Position::Inside => {
+            let [message_area] = Layout::vertical([Constraint::Length(height)])
+                .flex(Flex::Center)
+                .areas(area);
+            (area, message_area, font)
+        }
+    }
+}
+
+/// World coordinates `(x_bounds, y_bounds)` shown by a canvas filling `area`.
+///
+/// The square `[-bounds, bounds]` always fits and stays centred; the longer
+/// side of the area shows more of the world, so with `cell-aspect` matching
+/// the terminal font a world unit is as long across as it is up.
+pub fn world_bounds(area: Rect, config: &Config) -> ([f64; 2], [f64; 2]) {
+    let bounds = config.bounds;
+    let width = f64::from(area.width.max(1));
+    let height = f64::from(area.height.max(1)) * config.cell_aspect;
+    if width >= height {
+        let x = bounds * width / height;
+        ([-x, x], [-bounds, bounds])
+    } else {
+        let y = bounds * height / width;
+        ([-bounds, bounds], [-y, y])
+    }
+}
+
+/// Draw the pulsing heart and the particles around it filling `area`.
+fn draw_canvas(
+    buf: &mut Buffer,
+    area: Rect,
+    config: &Config,
+    geometry: &mut Geometry,
+    view: &View,
+    particles: &Particles,
+    seconds: f64,
+) {
+    let color = config.color(seconds);
+    let (x_bounds, y_bounds) = world_bounds(area, config);
+
+    let (dots_x, dots_y) = config.marker.resolution();
+    let grid = (
+        usize::from(area.width) * dots_x,
+        usize::from(area.height) * dots_y,
+    );
+    let density = f64::max(
+        grid.0 as f64 / (x_bounds[1] - x_bounds[0]),
+        grid.1 as f64 / (y_bounds[1] - y_bounds[0]),
+    );
+    let beat = config.heartbeat().scale(seconds);
+    let phase = config.phase(seconds);
+
+    // The solid heart is rendered in full here, the flat one from its outlines
+    let dots = if config.solid {
+        let pose = Pose {
+            spin: seconds * config.spin * TAU,
+            scale: beat,
+            view: *view,
+        };
+        let mesh = geometry.mesh();
+        Some(mesh.render(&pose, x_bounds, y_bounds, grid.0, grid.1))
+    } else {
+        geometry.update(config, density * view.zoom);
+        None
+    };
+    let outlines: Vec<Vec<Point>> = match dots {
+        Some(_) => Vec::new(),
+        None => geometry
+            .outlines()
+            .iter()
+            .map(|outline| outline.iter().map(|&(x, y)| (x * beat, y * beat)).collect())
+            .collect(),
+    };
+
+    let canvas = Canvas::default()
+        .marker(config.marker.symbol())
+        .x_bounds(x_bounds)
+        .y_bounds(y_bounds)
+        .paint(|ctx| {
+            if let Some(dots) = &dots {
+                draw_solid(ctx, dots, config, phase);
+            } else {
+                if config.filled {
+                    let placed: Vec<Vec<Point>> = outlines
+                        .iter()
+                        .map(|outline| outline.iter().map(|&point| view.apply(point)).collect())
+                        .collect();
+                    draw_fill(ctx, &placed, config, color, (x_bounds, y_bounds), grid);
+                }
+                draw_outline(ctx, &outlines, config, view, phase);
+            }
+            draw_particles(ctx, particles, config);
+        });
+
+    canvas.render(area, buf);
+}
+
+/// Draw the message as a `Paragraph` over `area`, applying its effect at `seconds`.
+///
+/// Only the cells under the text are touched, so a message inside the heart
+/// leaves the canvas around it visible.
+fn draw_message(
+    buf: &mut Buffer,
+    area: Rect,
+    config: &Config,
+    text: &str,
+    font: Font,
+    color: Color,
+    seconds: f64,
+) {
+    let width = message::width(text, font);
+    let speed = config.message_speed;
+    let (shown, color) = match config.message_effect {
+        Effect::Typewriter => (message::typed(text, seconds, speed), color),
+        Effect::FadeIn => {
+            let faded = palette::lerp(Color::Black, color, message::fade_in(seconds));
+            (text, config.quantize(faded))
+        }
+        Effect::None | Effect::Marquee => (text, color),
+    };
+    let lines: Vec<Line> = message::rows(shown, font)
+        .into_iter()
+        .map(Line::from)
+        .collect();
+    let paragraph = Paragraph::new(lines).fg(color);
+
+    if config.message_effect == Effect::Marquee {
+        // Enter from the right edge, then scroll off to the left
+        let span = usize::from(area.width);
+        let offset = message::marquee_offset(seconds, speed, width, span);
+        let (x, skip) = match span.checked_sub(offset) {
+            Some(x) => (x as u16, 0),
+            None => (0, (offset - span).min(usize::from(u16::MAX)) as u16),
+        };
+        let area = Rect {
+            x: area.x + x,
+            width: area.width - x,
+            ..area
+        };
+        paragraph.scroll((0, skip)).render(area, buf);
+    } else {
+        // Left-aligned within the final width, so typing does not shift the text
+        let [area] =
+            Layout::horizontal([Constraint::Length(width.min(usize::from(u16::MAX)) as u16)])
+                .flex(Flex::Center)
+                .areas(area);
+        paragraph.render(area, buf);
+    }
+}
+
+/// Render a centred popup describing why the config file was rejected.
+pub fn draw_error(frame: &mut Frame, message: &str) {
+    let area = frame.area();
+    let width = area.width.saturating_sub(4).min(72);
+    let [popup] = Layout::horizontal([Constraint::Length(width)])
+        .flex(Flex::Center)
+        .areas(area);
+    let [popup] = Layout::vertical([Constraint::Length(8)])
+        .flex(Flex::Center)
+        .areas(popup);
+
+    let block = Block::bordered()
+        .title(" config error - keeping last good settings ")
+        .red();
+    let text = Paragraph::new(message)
+        .wrap(Wrap { trim: false })
+        .block(block);
+
+    frame.render_widget(Clear, popup);
+    frame.render_widget(text, popup);
+}
+
+/// Show a message board note in a box near the top, `seconds` after it went
+/// up, in its own color or `color`.
+pub fn draw_note(frame: &mut Frame, note: &Note, color: Color, seconds: f64, config: &Config) {
+    // Widest box, in cells
+    const MAX_WIDTH: u16 = 64;
+
+    let area = frame.area();
+    let title = note.from.as_ref().map(|from| format!(" from {from} "));
+    let longest = note
+        .text
+        .lines()
+        .chain(title.as_deref())
+        .map(|line| line.chars().count())
+        .max()
+        .unwrap_or(0);
+    let width = (longest.min(usize::from(MAX_WIDTH)) as u16 + 4)
+        .min(MAX_WIDTH)
+        .min(area.width.saturating_sub(2));
+    let text_width = usize::from(width.saturating_sub(4)).max(1);
+
+    let color = config.quantize(note.color.unwrap_or(color));
+    let (shown, color) = match note.effect {
+        NoteEffect::Typewriter => (
+            message::typed(&note.text, seconds, config.message_speed),
+            color,
+        ),
+        NoteEffect::FadeIn => {
+            let faded = palette::lerp(Color::Black, color, message::fade_in(seconds));
+            (note.text.as_str(), config.quantize(faded))
+        }
+        NoteEffect::None | NoteEffect::Burst | NoteEffect::Confetti => (note.text.as_str(), color),
+    };
+    // Break long lines at the box edge, so typing never reflows the text
+    let rows = |text: &str| -> Vec<String> {
+        text.split('\n')
+            .flat_map(|line| {
+                let chars: Vec<char> = line.chars().collect();
+                if chars.is_empty() {
+                    vec![String::new()]
+                } else {
+                    chars.chunks(text_width).map(String::from_iter).collect()
+                }
+            })
+            .collect()
+    };
+    let height = (rows(&note.text).len() as u16 + 2).min(area.height);
+
+    let [popup] = Layout::horizontal([Constraint::Length(width)])
+        .flex(Flex::Center)
+        .areas(area);
+    let popup = Rect {
+        y: popup.y + u16::from(area.height > height),
+        height,
+        ..popup
+    };
+
+    let mut block = Block::bordered().padding(Padding::horizontal(1)).fg(color);
+    if let Some(title) = title {
+        block = block.title(title);
+    }
+    let lines: Vec<Line> = rows(shown).into_iter().map(Line::from).collect();
+    frame.render_widget(Clear, popup);
+    frame.render_widget(Paragraph::new(lines).fg(color).block(block), popup);
+}
+
+/// Show the playback state, e.g. `paused`, in the top right corner.
+pub fn draw_status(frame: &mut Frame, status: &str) {
+    let area = frame.area();
+    let [line, _] = Layout::vertical([Constraint::Length(1), Constraint::Min(0)]).areas(area);
+    frame.render_widget(
+        Line::from(format!(" {status} ")).dim().right_aligned(),
+        line,
+    );
+}
+
+/// Paint the inside of the innermost outline dot by dot.
+fn draw_fill(
+    ctx: &mut Context,
+    outlines: &[Vec<Point>],
+    config: &Config,
+    color: Color,
+    (x_bounds, y_bounds): ([f64; 2], [f64; 2]),
+    (cols, rows): (usize, usize),
+) {
+    let filled = fill::rasterize(outlines, x_bounds, y_bounds, cols, rows);
+
+    match config.fill_style {
+        FillStyle::Solid => {
+            let color = config
+                .fill_color
+                .map_or(color, |fill| config.quantize(fill));
+            for row in &filled {
+                ctx.draw(&Points {
+                    coords: &row.points,
+                    color,
+                });
+            }
+        }
+        FillStyle::Gradient => {
+            let Some((top, bottom)) = fill::vertical_extent(outlines) else {
+                return;
+            };
+            let [from, to] = config.gradient;
+            for row in &filled {
+                let t = (top - row.y) / (top - bottom).max(f64::EPSILON);
+                ctx.draw(&Points {
+                    coords: &row.points,
+                    color: config.quantize(palette::lerp(from, to, t)),
+                });
+            }
+        }
+    }
+}
+
+/// Draw a thick outline by stacking outward-scaled copies of `outlines`.
+///
+/// Colors run along each outline (`spread`) and step between layers
+/// (`layer-shift`), starting from palette position `phase`. Points are batched
+/// by color so each distinct color costs a single `Points` call. Layers are
+/// scaled before `view` places them, so they stay centred on the shape.
+fn draw_outline(
+    ctx: &mut Context,
+    outlines: &[Vec<Point>],
+    config: &Config,
+    view: &View,
+    phase: f64,
+) {
+    // Color changes per outline when the palette is spread along it
+    const SEGMENTS: usize = 96;
+
+    let gradient = config.gradient();
+    let segments = if config.spread == 0.0 { 1 } else { SEGMENTS };
+    let mut batches: Vec<(Color, Vec<Point>)> = Vec::new();
+
+    for layer in 0..config.layers {
+        // Scale each layer outward for thickness effect
+        let scale = 1.0 + f64::from(layer) * config.thickness;
+        let layer_phase = phase + f64::from(layer) * config.layer_shift;
+
+        for outline in outlines {
+            let chunk = outline.len().div_ceil(segments).max(1);
+            for (i, points) in outline.chunks(chunk).enumerate() {
+                // Curve parameter at the middle of this run of points
+                let t = (i as f64 + 0.5) * chunk as f64 / outline.len() as f64;
+                let color = config.quantize(gradient.sample(layer_phase + config.spread * t));
+
+                let scaled = points
+                    .iter()
+                    .map(|&(x, y)| view.apply((x * scale, y * scale)));
+                match batches.iter_mut().find(|(c, _)| *c == color) {
+                    Some((_, batch)) => batch.extend(scaled),
+                    None => batches.push((color, scaled.collect())),
+                }
+            }
+        }
+    }
+
+    for (color, coords) in &batches {
+        ctx.draw(&Points {
+            coords,
+            color: *color,
+        });
+    }
+}
+
+/// Draw the dots of the solid heart, colored by depth and shaded by light.
+///
+/// The palette runs from the back of the heart to the front starting at
+/// `phase`, and the light is rounded to a few levels so dots share colors and
+/// batch into few `Points` calls.
+fn draw_solid(ctx: &mut Context, dots: &[Dot], config: &Config, phase: f64) {
+    // Palette distance from the back of the heart to the front
+    const DEPTH_SPAN: f64 = 0.5;
+    // Distinct brightness levels
+    const LEVELS: f64 = 8.0;
+
+    let gradient = config.gradient();
+    let mut batches: Vec<(Color, Vec<Point>)> = Vec::new();
+    for dot in dots {
+        let base = gradient.sample(phase + DEPTH_SPAN * dot.depth);
+        let light = (dot.light * LEVELS).round() / LEVELS;
+        let color = config.quantize(palette::lerp(Color::Black, base, light));
+        match batches.iter_mut().find(|(c, _)| *c == color) {
+            Some((_, batch)) => batch.push(dot.point),
+            None => batches.push((color, vec![dot.point])),
+        }
+    }
+
+    for (color, coords) in &batches {
+        ctx.draw(&Points {
+            coords,
+            color: *color,
+        });
+    }
+}
+
+/// Draw every particle, faded towards black as it ages.
+///
+/// Hearts and sparkles are printed as symbols on top of the dot grid, while
+/// confetti pieces are single dots.
+fn draw_particles(ctx: &mut Context, particles: &Particles, config: &Config) {
+    for particle in particles.iter() {
+        let life = particle.life();
+        let color = config.quantize(palette::lerp(Color::Black, particle.color, life));
+        let (x, y) = particle.position;
+        match particle.kind {
+            Kind::Heart => ctx.print(x, y, "♥".fg(color)),
+            Kind::Sparkle => {
+                let symbol = match life {
+                    0.66.. => "✦",
+                    0.33.. => "+",
+                    _ => "·",
+                };
+                ctx.print(x, y, symbol.fg(color));
+            }
+            Kind::Confetti => ctx.draw(&Points {
+                coords: &[(x, y)],
+                color,
+            }),
+        }
+    }
+}
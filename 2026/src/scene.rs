@@ -0,0 +1,513 @@
+// src/scene.rs
+//! Scenes and the playlist that shows them one after another.
+//!
+//! A [`Scene`] is anything that can be stepped and drawn. The [`Playlist`]
+//! picks the scene for the current animation time from the `[[scenes]]` in the
+//! config, blends between neighbours with a transition and lets the user skip
+//! ahead or back. Without any `[[scenes]]` it holds the configured heart alone,
+//! forever.
+
+use std::time::Duration;
+
+use chrono::NaiveDateTime;
+use clap::ValueEnum;
+use ratatui::{
+    Frame,
+    buffer::{Buffer, Cell},
+    style::Color,
+};
+use serde::Deserialize;
+
+use crate::{
+    config::{Config, deserialize_duration},
+    countdown,
+    message::{Effect, Font, Position},
+    palette,
+    particles::Particles,
+    view::View,
+    widget::{HeartState, HeartWidget},
+};
+
+/// Something the playlist can show.
+pub trait Scene {
+    /// Called when the scene comes on screen, to start it afresh.
+    fn start(&mut self) {}
+
+    /// Advance by `dt` seconds, `seconds` into the scene.
+    fn update(&mut self, config: &Config, view: &View, seconds: f64, dt: f64);
+
+    /// Draw the scene as it is `seconds` into it.
+    fn render(&mut self, frame: &mut Frame, config: &Config, view: &View, seconds: f64);
+
+    /// Particles that clicks and key presses add to, if the scene has any.
+    fn particles(&mut self) -> Option<&mut Particles> {
+        None
+    }
+
+    /// The settings the scene draws with, derived from `config`.
+    fn config(&self, config: &Config) -> Config {
+        config.clone()
+    }
+}
+
+/// Built-in scenes, each a variation on the configured heart.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum SceneKind {
+    /// The heart exactly as configured.
+    #[default]
+    Heart,
+    /// The message typed out in big letters inside the heart.
+    Message,
+    /// Hearts rising thickly and bursts of confetti.
+    Shower,
+    /// The solid 3D heart.
+    Solid,
+    /// Time left until `countdown-target` beside the heart, then a celebration.
+    Countdown,
+}
+
+impl SceneKind {
+    /// The settings this scene draws with at local time `now`, derived from `base`.
+    pub fn configure(self, base: &Config, message: Option<&str>, now: NaiveDateTime) -> Config {
+        let mut config = base.clone();
+        if let Some(message) = message {
+            config.message = Some(message.to_owned());
+        }
+        match self {
+            SceneKind::Heart => {}
+            SceneKind::Message => {
+                config
+                    .message
+                    .get_or_insert_with(|| String::from("Be mine"));
+                celebrate(&mut config);
+            }
+            SceneKind::Shower => config.heart_rate = config.heart_rate.max(6.0),
+            SceneKind::Solid => config.solid = true,
+            SceneKind::Countdown if self.celebrates(base, now) => {
+                let text = message.unwrap_or(&config.countdown_message);
+                config.message = Some(text.to_owned());
+                config.heart_rate = config.heart_rate.max(6.0);
+                celebrate(&mut config);
+            }
+            SceneKind::Countdown => {
+                let left = countdown::remaining(config.countdown_target, now);
+                config.message = Some(countdown::label(left));
+                config.message_position = Position::Beside;
+                config.message_effect = Effect::None;
+            }
+        }
+        config
+    }
+
+    /// Whether this is a countdown that has reached zero at local time `now`.
+    pub fn celebrates(self, config: &Config, now: NaiveDateTime) -> bool {
+        self == SceneKind::Countdown && countdown::remaining(config.countdown_target, now).is_zero()
+    }
+}
+
+/// Show the message in big letters inside the heart, typed out unless it
+/// already has an effect.
+fn celebrate(config: &mut Config) {
+    config.message_font = Font::Big;
+    config.message_position = Position::Inside;
+    if config.message_effect == Effect::None {
+        config.message_effect = Effect::Typewriter;
+    }
+}
+
+/// How one scene gives way to the next.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Transition {
+    /// Switch at once.
+    Cut,
+    /// Fade the old scene into the new one.
+    #[default]
+    Crossfade,
+    /// Slide the new scene in from the left edge.
+    Wipe,
+}
+
+/// One `[[scenes]]` entry of the config.
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields, rename_all = "kebab-case")]
+pub struct SceneConfig {
+    /// What the scene shows.
+    pub kind: SceneKind,
+    /// How long it stays; `scene-duration` when unset.
+    #[serde(deserialize_with = "deserialize_duration")]
+    pub duration: Option<Duration>,
+    /// How it replaces the scene before; `transition` when unset.
+    pub transition: Option<Transition>,
+    /// Message for this scene instead of the configured one.
+    pub message: Option<String>,
+}
+
+/// The configured heart in one of the [`SceneKind`] variations, with its own
+/// particles.
+#[derive(Debug, Clone)]
+pub struct HeartScene {
+    kind: SceneKind,
+    message: Option<String>,
+    seed: u64,
+    state: HeartState,
+    /// Confetti bursts thrown so far by a shower or celebration.
+    bursts: u64,
+    /// The config and, for a countdown, the second the widget was last
+    /// derived for.
+    built: Option<(Config, Option<i64>)>,
+    /// The widget last drawn.
+    widget: Option<HeartWidget>,
+}
+
+impl HeartScene {
+    /// Seconds between confetti bursts of a shower or celebration.
+    const SHOWER_INTERVAL: f64 = 1.5;
+
+    /// A scene of `kind`, showing `message` if given, whose particles follow `seed`.
+    pub fn new(kind: SceneKind, message: Option<String>, seed: u64) -> Self {
+        Self {
+            kind,
+            message,
+            seed,
+            state: HeartState::new(seed),
+            bursts: 0,
+            built: None,
+            widget: None,
+        }
+    }
+
+    /// The widget for this frame, deriving its settings from `base` again
+    /// only when `base` changed or, for a countdown, once a second.
+    fn widget(
+        &mut self,
+        base: &Config,
+        view: &View,
+        seconds: f64,
+        now: NaiveDateTime,
+    ) -> HeartWidget {
+        let second = (self.kind == SceneKind::Countdown).then(|| now.and_utc().timestamp());
+        let current = self
+            .built
+            .as_ref()
+            .is_some_and(|(config, built)| config == base && *built == second);
+        let widget = match self.widget.take() {
+            Some(widget) if current => widget,
+            _ => {
+                self.built = Some((base.clone(), second));
+                HeartWidget::new().config(self.kind.configure(base, self.message.as_deref(), now))
+            }
+        };
+        widget.view(*view).seconds(seconds)
+    }
+
+    /// Keep `widget` for the next frame.
+    fn keep(&mut self, widget: HeartWidget) {
+        self.widget = Some(widget);
+    }
+}
+
+impl Scene for HeartScene {
+    fn start(&mut self) {
+        self.state = HeartState::new(self.seed);
+        self.bursts = 0;
+    }
+
+    fn update(&mut self, config: &Config, view: &View, seconds: f64, dt: f64) {
+        let now = countdown::now();
+        let widget = self.widget(config, view, seconds, now);
+        widget.update(&mut self.state, dt);
+        if self.kind == SceneKind::Shower || self.kind.celebrates(config, now) {
+            let due = (seconds / Self::SHOWER_INTERVAL).floor().max(0.0) as u64;
+            if due > self.bursts {
+                self.bursts = due;
+                let bounds = self.state.world_bounds(widget.settings());
+                self.state
+                    .particles_mut()
+                    .confetti(widget.settings(), bounds);
+            }
+        }
+        self.keep(widget);
+    }
+
+    fn render(&mut self, frame: &mut Frame, config: &Config, view: &View, seconds: f64) {
+        let now = countdown::now();
+        let widget = self.widget(config, view, seconds, now);
+        frame.render_stateful_widget(&widget, frame.area(), &mut self.state);
+        self.keep(widget);
+    }
+
+    fn particles(&mut self) -> Option<&mut Particles> {
+        Some(self.state.particles_mut())
+    }
+
+    fn config(&self, config: &Config) -> Config {
+        self.kind
+            .configure(config, self.message.as_deref(), countdown::now())
+    }
+}
+
+/// Scenes shown in turn, looping, on the animation clock.
+pub struct Playlist {
+    entries: Vec<Entry>,
+    /// The config settings the entries were built from.
+    source: Source,
+    /// Animation seconds at which the playlist started, moved by skipping.
+    offset: f64,
+    /// Index of the scene last updated and the loop it was in.
+    current: (usize, i64),
+}
+
+struct Entry {
+    scene: Box<dyn Scene>,
+    /// Seconds on screen; `None` for a lone scene that stays forever.
+    duration: Option<f64>,
+    transition: Transition,
+}
+
+/// Everything in the config that decides what the playlist holds.
+#[derive(Debug, Clone, PartialEq)]
+struct Source {
+    scenes: Vec<SceneConfig>,
+    scene_duration: Duration,
+    transition: Transition,
+    countdown: bool,
+    seed: Option<u64>,
+}
+
+impl Source {
+    fn new(config: &Config) -> Self {
+        Self {
+            scenes: config.scenes.clone(),
+            scene_duration: config.scene_duration,
+            transition: config.transition,
+            countdown: config.countdown,
+            seed: config.seed,
+        }
+    }
+}
+
+impl Playlist {
+    /// The scenes listed in `config`, or the configured heart (or countdown) alone.
+    pub fn new(config: &Config) -> Self {
+        let source = Source::new(config);
+        let seed = config.seed.unwrap_or(0);
+        let entries = if config.scenes.is_empty() {
+            let kind = match config.countdown {
+                true => SceneKind::Countdown,
+                false => SceneKind::Heart,
+            };
+            vec![Entry {
+                scene: Box::new(HeartScene::new(kind, None, seed)),
+                duration: None,
+                transition: Transition::Cut,
+            }]
+        } else {
+            config
+                .scenes
+                .iter()
+                .map(|scene| Entry {
+                    scene: Box::new(HeartScene::new(scene.kind, scene.message.clone(), seed)),
+                    duration: Some(
+                        scene
+                            .duration
+                            .unwrap_or(config.scene_duration)
+                            .as_secs_f64(),
+                    ),
+                    transition: scene.transition.unwrap_or(config.transition),
+                })
+                .collect()
+        };
+        Self {
+            entries,
+            source,
+            offset: 0.0,
+            current: (0, 0),
+        }
+    }
+
+    /// Number of scenes.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Whether the playlist has no scenes; never true.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Index of the scene on screen at `seconds` and how far into it that is.
+    ///
+    /// A lone scene runs on the animation clock itself.
+    pub fn position(&self, seconds: f64) -> (usize, f64) {
+        let total = self.total();
+        if total <= 0.0 {
+            return (0, seconds);
+        }
+        let mut local = (seconds - self.offset).rem_euclid(total);
+        for (index, entry) in self.entries.iter().enumerate() {
+            let duration = entry.duration.unwrap_or_default();
+            if local < duration {
+                return (index, local);
+            }
+            local -= duration;
+        }
+        (self.entries.len() - 1, local)
+    }
+
+    /// Advance the scene on screen at `seconds` by `dt`, and the one it is
+    /// replacing while their transition runs, first rebuilding the playlist
+    /// if the config now lists other scenes.
+    pub fn update(&mut self, config: &Config, view: &View, seconds: f64, dt: f64) {
+        if self.source != Source::new(config) {
+            *self = Self::new(config);
+        }
+        let (index, local) = self.position(seconds);
+        let shown = (index, self.lap(seconds));
+        let entry = &mut self.entries[index];
+        if shown != self.current {
+            self.current = shown;
+            entry.scene.start();
+        }
+        entry.scene.update(config, view, local, dt);
+        if let Some((previous, before_local, _)) = self.outgoing(config, seconds)
+            && previous != index
+        {
+            self.entries[previous]
+                .scene
+                .update(config, view, before_local, dt);
+        }
+    }
+
+    /// Draw the scene on screen at `seconds`, blended with the one before
+    /// while its transition runs.
+    pub fn render(&mut self, frame: &mut Frame, config: &Config, view: &View, seconds: f64) {
+        let (index, local) = self.position(seconds);
+        let Some((previous, before_local, progress)) = self
+            .outgoing(config, seconds)
+            .filter(|&(previous, ..)| previous != index)
+        else {
+            self.entries[index].scene.render(frame, config, view, local);
+            return;
+        };
+
+        let transition = self.entries[index].transition;
+        self.entries[previous]
+            .scene
+            .render(frame, config, view, before_local);
+        let from = frame.buffer_mut().clone();
+        frame.buffer_mut().reset();
+        self.entries[index].scene.render(frame, config, view, local);
+        blend(&from, frame.buffer_mut(), transition, progress, config);
+    }
+
+    /// Seconds in one pass through every scene; zero for a lone scene.
+    fn total(&self) -> f64 {
+        self.entries.iter().filter_map(|entry| entry.duration).sum()
+    }
+
+    /// How many times the playlist has looped by `seconds`, so a scene shown
+    /// again, even as the only one, starts afresh.
+    fn lap(&self, seconds: f64) -> i64 {
+        let total = self.total();
+        if total <= 0.0 {
+            return 0;
+        }
+        (seconds - self.offset).div_euclid(total) as i64
+    }
+
+    /// The scene being replaced at `seconds` while a transition runs: its
+    /// index, how far into it that is and how far the transition has got.
+    fn outgoing(&self, config: &Config, seconds: f64) -> Option<(usize, f64, f64)> {
+        let (index, local) = self.position(seconds);
+        let entry = &self.entries[index];
+        let time = config
+            .transition_time
+            .min(entry.duration.unwrap_or_default());
+        let elapsed = seconds - self.offset;
+        if entry.transition == Transition::Cut || local >= time || elapsed < time {
+            return None;
+        }
+        let previous = (index + self.entries.len() - 1) % self.entries.len();
+        let before_local = self.entries[previous].duration.unwrap_or_default() + local;
+        Some((previous, before_local, local / time))
+    }
+
+    /// Particles of the scene on screen at `seconds`, if it has any.
+    pub fn particles(&mut self, seconds: f64) -> Option<&mut Particles> {
+        let (index, _) = self.position(seconds);
+        self.entries[index].scene.particles()
+    }
+
+    /// Settings of the scene on screen at `seconds`, derived from `config`,
+    /// e.g. to map the mouse onto the canvas it draws.
+    pub fn config(&self, config: &Config, seconds: f64) -> Config {
+        let (index, _) = self.position(seconds);
+        self.entries[index].scene.config(config)
+    }
+
+    /// Animation seconds at which the playlist started, moved by skipping.
+    pub fn offset(&self) -> f64 {
+        self.offset
+    }
+
+    /// Move the start of the playlist to `offset` animation seconds, e.g. to
+    /// show the same scene as another heart.
+    pub fn set_offset(&mut self, offset: f64) {
+        self.offset = offset;
+    }
+
+    /// Skip to the start of the scene after the one on screen at `seconds`.
+    pub fn next(&mut self, seconds: f64) {
+        let (index, local) = self.position(seconds);
+        if let Some(duration) = self.entries[index].duration {
+            self.offset -= duration - local;
+        }
+    }
+
+    /// Skip back to the start of the scene before the one on screen at `seconds`.
+    pub fn previous(&mut self, seconds: f64) {
+        let (index, local) = self.position(seconds);
+        let previous = (index + self.entries.len() - 1) % self.entries.len();
+        if let Some(duration) = self.entries[previous].duration {
+            self.offset += local + duration;
+        }
+    }
+}
+
+/// Mix the frame being replaced, `from`, into the new frame `to`, `progress`
+/// of the way through `transition`.
+pub fn blend(
+    from: &Buffer,
+    to: &mut Buffer,
+    transition: Transition,
+    progress: f64,
+    config: &Config,
+) {
+    let progress = progress.clamp(0.0, 1.0);
+    let width = usize::from(to.area.width).max(1);
+    for (index, (old, new)) in from.content.iter().zip(&mut to.content).enumerate() {
+        match transition {
+            Transition::Cut => {}
+            Transition::Wipe => {
+                // The new frame covers the columns left of the edge
+                let edge = progress * width as f64;
+                if (index % width) as f64 >= edge {
+                    *new = old.clone();
+                }
+            }
+            Transition::Crossfade => {
+                let ink = |cell: &Cell| match cell.symbol() {
+                    " " => Color::Black,
+                    _ => cell.fg,
+                };
+                let fg = palette::lerp(ink(old), ink(new), progress);
+                if new.symbol() == " " || (progress < 0.5 && old.symbol() != " ") {
+                    new.set_symbol(old.symbol());
+                }
+                new.set_fg(config.quantize(fg));
+            }
+        }
+    }
+}
@@ -0,0 +1,155 @@
+// src/screensaver.rs
+//! The heart as a screensaver: it wanders the screen like the old DVD logo,
+//! bouncing off the edges, while its colors slowly turn round the hue wheel.
+//!
+//! Every so often the whole picture moves by one cell, so nothing stays lit in
+//! the same place long enough to burn in.
+
+use std::f64::consts::{FRAC_PI_2, TAU};
+
+use ratatui::{buffer::Buffer, layout::Rect};
+
+use crate::{
+    config::Config,
+    palette,
+    render::{canvas_area, world_bounds},
+    shape::Point,
+    view::View,
+};
+
+/// Speed of the wandering heart in world units per second.
+const SPEED: f64 = 0.5;
+/// Zoom of the wandering heart, leaving it room to move.
+const ZOOM: f64 = 0.5;
+/// Seconds per full turn of the hue wheel.
+const HUE_PERIOD: f64 = 120.0;
+/// Seconds between burn-in shifts.
+const SHIFT_INTERVAL: f64 = 60.0;
+/// Cell offsets of the whole picture, taken in turn.
+const SHIFTS: [(u16, u16); 4] = [(0, 0), (1, 0), (1, 1), (0, 1)];
+
+/// Where the wandering heart is and where it is heading.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Screensaver {
+    position: Point,
+    velocity: Point,
+    /// Seconds since the screensaver started.
+    seconds: f64,
+    /// The heart's [`reach`] and the config it was measured for.
+    reach: Option<(Config, Point)>,
+}
+
+impl Screensaver {
+    /// A heart setting off from the centre diagonally, in a direction picked
+    /// by `seed`.
+    pub fn new(seed: u64) -> Self {
+        // Off the diagonal, so the path does not retrace itself too soon
+        let angle = 0.6 + FRAC_PI_2 * (seed % 4) as f64;
+        Self {
+            position: (0.0, 0.0),
+            velocity: (SPEED * angle.cos(), SPEED * angle.sin()),
+            seconds: 0.0,
+            reach: None,
+        }
+    }
+
+    /// Move the heart on by `dt` seconds, bouncing off the edges of the
+    /// canvas drawn in `area`.
+    pub fn update(&mut self, area: Rect, config: &Config, dt: f64) {
+        self.seconds += dt;
+        let (x_bounds, y_bounds) = world_bounds(canvas_area(area, config), config);
+        let (reach_x, reach_y) = match &self.reach {
+            Some((measured, reach)) if measured == config => *reach,
+            _ => self.reach.insert((config.clone(), reach(config))).1,
+        };
+        let (x, vx) = bounce(self.position.0, self.velocity.0 * dt, x_bounds, reach_x);
+        let (y, vy) = bounce(self.position.1, self.velocity.1 * dt, y_bounds, reach_y);
+        self.position = (x, y);
+        self.velocity = (self.velocity.0 * vx, self.velocity.1 * vy);
+    }
+
+    /// Where the heart is drawn now.
+    pub fn view(&self) -> View {
+        View {
+            offset: self.position,
+            zoom: ZOOM,
+            ..View::default()
+        }
+    }
+
+    /// How far the colors have turned round the hue wheel, in radians.
+    pub fn hue(&self) -> f64 {
+        (self.seconds / HUE_PERIOD * TAU).rem_euclid(TAU)
+    }
+
+    /// Columns and rows the whole picture is moved right and down by.
+    pub fn shift(&self) -> (u16, u16) {
+        let step = (self.seconds / SHIFT_INTERVAL) as usize;
+        SHIFTS[step % SHIFTS.len()]
+    }
+
+    /// Turn the hue of a drawn frame and move it by [`Self::shift`].
+    pub fn finish(&self, buf: &mut Buffer, config: &Config) {
+        let hue = self.hue();
+        for cell in &mut buf.content {
+            if cell.symbol() != " " {
+                cell.set_fg(config.quantize(palette::rotate_hue(cell.fg, hue)));
+            }
+        }
+
+        let (dx, dy) = self.shift();
+        if (dx, dy) == (0, 0) {
+            return;
+        }
+        let area = buf.area;
+        let frame = buf.clone();
+        for y in area.top()..area.bottom() {
+            for x in area.left()..area.right() {
+                buf[(x, y)] = match (x.checked_sub(dx), y.checked_sub(dy)) {
+                    (Some(sx), Some(sy)) if sx >= area.left() && sy >= area.top() => {
+                        frame[(sx, sy)].clone()
+                    }
+                    _ => Default::default(),
+                };
+            }
+        }
+    }
+}
+
+/// Half the width and height of the heart at its largest, in world units.
+///
+/// Samples the whole outline, so it is measured again only when the config
+/// changes.
+fn reach(config: &Config) -> Point {
+    let outlines = config.shape().outlines(256);
+    let (width, height) = outlines
+        .iter()
+        .flatten()
+        .fold((0.0, 0.0), |(w, h): Point, &(x, y)| {
+            (w.max(x.abs()), h.max(y.abs()))
+        });
+    let layers = 1.0 + f64::from(config.layers.saturating_sub(1)) * config.thickness;
+    let scale = ZOOM * layers * (1.0 + config.pulse);
+    (width * scale, height * scale)
+}
+
+/// Move `position` by `step` within `bounds` less `reach` on either side,
+/// returning the new position and `-1` if it bounced off an edge or `1` if not.
+///
+/// Only a heart heading out bounces; one left outside by a shrinking screen
+/// is pulled back in.
+fn bounce(position: f64, step: f64, [low, high]: [f64; 2], reach: f64) -> (f64, f64) {
+    let (low, high) = (low + reach, high - reach);
+    if low >= high {
+        // No room to wander: stay in the middle
+        return (0.5 * (low + high), 1.0);
+    }
+    let next = position + step;
+    if next > high && step > 0.0 {
+        ((2.0 * high - next).max(low), -1.0)
+    } else if next < low && step < 0.0 {
+        ((2.0 * low - next).min(high), -1.0)
+    } else {
+        (next.clamp(low, high), 1.0)
+    }
+}
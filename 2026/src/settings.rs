@@ -0,0 +1,138 @@
+// src/settings.rs
+//! The config file merged with command-line overrides, kept up to date while running.
+
+use std::path::Path;
+
+use color_eyre::{
+    Result,
+    eyre::{WrapErr, bail},
+};
+use ratatui_heart::{
+    config::{Config, ConfigWatcher},
+    palette::ColorDepth,
+};
+
+use crate::cli::Cli;
+
+/// Settings currently in effect and the reason the latest reload was rejected, if any.
+#[derive(Debug)]
+pub struct Settings {
+    cli: Cli,
+    watcher: Option<ConfigWatcher>,
+    /// Last good settings.
+    pub config: Config,
+    /// Why the config file is currently being ignored.
+    pub error: Option<String>,
+}
+
+impl Settings {
+    /// Load the config file and apply `cli` on top.
+    ///
+    /// A broken config file is reported through [`Settings::error`] rather than
+    /// failing, but invalid command-line arguments are a hard error.
+    pub fn load(cli: Cli) -> Result<Self> {
+        if let Some(path) = &cli.config
+            && !path.exists()
+        {
+            bail!("config file {} does not exist", path.display());
+        }
+        let watcher = cli.config_path().map(ConfigWatcher::new);
+
+        let path = watcher.as_ref().map(ConfigWatcher::path);
+        let (config, error) = match load(&cli, path) {
+            Ok(config) => (config, None),
+            Err(err) => (load(&cli, None)?, Some(format!("{err:#}"))),
+        };
+        Ok(Self {
+            cli,
+            watcher,
+            config,
+            error,
+        })
+    }
+
+    /// Apply config file edits, keeping the last good settings on error.
+    ///
+    /// Returns whether the file changed, successfully or not.
+    pub fn reload(&mut self) -> bool {
+        let Some(reloaded) = self.watcher.as_mut().and_then(ConfigWatcher::poll) else {
+            return false;
+        };
+        match reloaded.and_then(|config| with_overrides(&self.cli, config)) {
+            Ok(config) => {
+                self.config = config;
+                self.error = None;
+            }
+            Err(err) => self.error = Some(format!("{err:#}")),
+        }
+        true
+    }
+}
+
+/// Load the config file at `path` (if it exists) and apply command-line overrides.
+fn load(cli: &Cli, path: Option<&Path>) -> Result<Config> {
+    let config = match path {
+        Some(path) if path.exists() => Config::load(path)?,
+        _ => Config::default(),
+    };
+    with_overrides(cli, config)
+}
+
+/// Apply command-line overrides and check the combination is still valid.
+fn with_overrides(cli: &Cli, mut config: Config) -> Result<Config> {
+    cli.apply(&mut config);
+    config
+        .validate()
+        .wrap_err("invalid combination of config file and command-line arguments")?;
+    // Exported files are not limited by the terminal they were made in
+    if cli.exports() && config.color_depth == ColorDepth::Auto {
+        config.color_depth = ColorDepth::TrueColor;
+    }
+    config.color_depth = config.color_depth.resolve();
+    Ok(config)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        fs::{self, File},
+        thread,
+        time::{Duration, SystemTime},
+    };
+
+    use clap::Parser;
+
+    use super::*;
+
+    #[test]
+    fn reloads_keep_command_line_flags_on_top() {
+        let path = std::env::temp_dir().join(format!(
+            "ratatui_heart-settings-{}.toml",
+            std::process::id()
+        ));
+        fs::write(&path, "fps = 10.0\nlayers = 2").unwrap();
+        let cli = Cli::try_parse_from([
+            "ratatui_heart",
+            "--config",
+            path.to_str().unwrap(),
+            "--fps",
+            "30",
+        ])
+        .unwrap();
+        let mut settings = Settings::load(cli).unwrap();
+        assert_eq!((settings.config.fps, settings.config.layers), (30.0, 2));
+
+        fs::write(&path, "fps = 20.0\nlayers = 3").unwrap();
+        File::options()
+            .write(true)
+            .open(&path)
+            .unwrap()
+            .set_modified(SystemTime::now() + Duration::from_secs(5))
+            .unwrap();
+        thread::sleep(Duration::from_millis(600));
+        assert!(settings.reload());
+        fs::remove_file(&path).unwrap();
+        assert_eq!((settings.config.fps, settings.config.layers), (30.0, 3));
+        assert!(settings.error.is_none());
+    }
+}
@@ -0,0 +1,299 @@
+// src/shape.rs
+//! Outlines that can be drawn by the layered heart renderer.
+//!
+//! Every shape is sized to fit the default `[-2, 2]` world with a little margin,
+//! so the outer thickness layers still stay on screen.
+
+use std::f64::consts::{PI, TAU};
+
+use clap::ValueEnum;
+use serde::Deserialize;
+
+/// A point in world coordinates.
+pub type Point = (f64, f64);
+
+/// Something the renderer can outline.
+pub trait Shape {
+    /// Sample every closed outline of the shape with `steps` points each.
+    ///
+    /// The renderer scales these outlines about the origin to build up thickness.
+    fn outlines(&self, steps: usize) -> Vec<Vec<Point>>;
+}
+
+/// Shapes selectable with `--shape`, `shape = "..."` or the `s` key.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ShapeKind {
+    /// Classic `16 sin^3 t` parametric heart.
+    #[default]
+    Heart,
+    /// Polar cardioid `r = 1 - sin t`.
+    Cardioid,
+    /// Implicit heart `(x² + y² - 1)³ - x²y³ = 0`.
+    Implicit,
+    /// Classic heart split down a jagged crack.
+    Broken,
+    /// Two smaller hearts overlapping each other.
+    Twin,
+    /// Five-pointed star.
+    Star,
+    /// Rose curve `r = cos(k t)`.
+    Rose,
+    /// Closed polyline through the `points` from the config file.
+    Polyline,
+}
+
+impl ShapeKind {
+    /// The next shape in cycling order, wrapping around at the end.
+    pub fn next(self) -> Self {
+        let all = Self::value_variants();
+        let index = all.iter().position(|&kind| kind == self).unwrap_or(0);
+        all[(index + 1) % all.len()]
+    }
+}
+
+/// The classic parametric heart.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Heart;
+
+impl Heart {
+    /// Point on the outline for curve parameter `t` in `[0, TAU]`.
+    ///
+    /// `t = 0` is the top cusp and `t = PI` the bottom tip.
+    pub fn point(t: f64) -> Point {
+        let x = 16.0 * t.sin().powi(3);
+        let y = 13.0 * t.cos() - 5.0 * (2.0 * t).cos() - 2.0 * (3.0 * t).cos() - (4.0 * t).cos();
+
+        // Normalize to the [-2, 2] world
+        (x / 10.0, y / 10.0)
+    }
+}
+
+impl Shape for Heart {
+    fn outlines(&self, steps: usize) -> Vec<Vec<Point>> {
+        vec![sample(steps, 0.0, TAU, Heart::point)]
+    }
+}
+
+/// A cardioid with its cusp at the top, centred on the origin.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Cardioid;
+
+impl Shape for Cardioid {
+    fn outlines(&self, steps: usize) -> Vec<Vec<Point>> {
+        vec![sample(steps, 0.0, TAU, |t| {
+            let r = 1.0 - t.sin();
+            (1.1 * r * t.cos(), 1.1 * (r * t.sin() + 1.0))
+        })]
+    }
+}
+
+/// The implicit heart curve, traced by solving for the radius along each ray.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ImplicitHeart;
+
+impl ImplicitHeart {
+    /// Distance from the origin to the curve in direction `t`.
+    ///
+    /// The curve is star-shaped around the origin and `f` changes sign exactly
+    /// once in `[0, 2]`, so plain bisection finds the root.
+    fn radius(t: f64) -> f64 {
+        let (sin, cos) = t.sin_cos();
+        let f = |r: f64| (r * r - 1.0).powi(3) - r.powi(5) * cos * cos * sin.powi(3);
+
+        let (mut lo, mut hi) = (0.0, 2.0);
+        for _ in 0..40 {
+            let mid = 0.5 * (lo + hi);
+            if f(mid) < 0.0 {
+                lo = mid;
+            } else {
+                hi = mid;
+            }
+        }
+        0.5 * (lo + hi)
+    }
+}
+
+impl Shape for ImplicitHeart {
+    fn outlines(&self, steps: usize) -> Vec<Vec<Point>> {
+        vec![sample(steps, 0.0, TAU, |t| {
+            let r = 1.45 * Self::radius(t);
+            (r * t.cos(), r * t.sin() - 0.15)
+        })]
+    }
+}
+
+/// The classic heart split into two halves along a zig-zag crack.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct BrokenHeart;
+
+impl BrokenHeart {
+    /// Horizontal offset of the crack at height `y`.
+    fn crack(y: f64) -> f64 {
+        const TEETH: f64 = 6.0;
+        let (top, bottom) = (Heart::point(0.0).1, Heart::point(PI).1);
+        let phase = (y - bottom) / (top - bottom) * TEETH;
+
+        // Triangle wave that fades out towards the bottom tip
+        let tri = 2.0 * (phase - (phase + 0.5).floor()).abs() - 0.5;
+        0.16 * tri * (phase / TEETH)
+    }
+
+    /// Points along the crack between the top cusp and the bottom tip.
+    fn crack_line(steps: usize, downwards: bool) -> Vec<Point> {
+        let (top, bottom) = (Heart::point(0.0).1, Heart::point(PI).1);
+        let (from, to) = if downwards {
+            (top, bottom)
+        } else {
+            (bottom, top)
+        };
+        sample(steps, from, to, |y| (Self::crack(y), y))
+    }
+}
+
+impl Shape for BrokenHeart {
+    fn outlines(&self, steps: usize) -> Vec<Vec<Point>> {
+        let half = steps / 2;
+
+        // Right half: outline from cusp to tip, then up the crack
+        let mut right = sample(half, 0.0, PI, Heart::point);
+        right.extend(Self::crack_line(half, false));
+
+        // Left half: outline from tip to cusp, then down the crack
+        let mut left = sample(half, PI, TAU, Heart::point);
+        left.extend(Self::crack_line(half, true));
+
+        // Tilt both halves outwards around the bottom tip and pull them apart
+        let tip = Heart::point(PI).1;
+        let tilt = |half: &[Point], dx: f64, angle: f64| {
+            let lifted = transform(half, 0.0, -tip, 0.0, 1.0);
+            transform(&lifted, dx, tip, angle, 1.0)
+        };
+        vec![tilt(&right, 0.06, -0.07), tilt(&left, -0.06, 0.07)]
+    }
+}
+
+/// Two overlapping, slightly tilted hearts.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct TwinHearts;
+
+impl Shape for TwinHearts {
+    fn outlines(&self, steps: usize) -> Vec<Vec<Point>> {
+        let heart = sample(steps, 0.0, TAU, Heart::point);
+        vec![
+            transform(&heart, -0.55, 0.25, 0.25, 0.62),
+            transform(&heart, 0.55, -0.2, -0.25, 0.62),
+        ]
+    }
+}
+
+/// A five-pointed star.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Star;
+
+impl Shape for Star {
+    fn outlines(&self, steps: usize) -> Vec<Vec<Point>> {
+        const POINTS: usize = 5;
+        let vertices: Vec<Point> = (0..2 * POINTS)
+            .map(|i| {
+                let r = if i % 2 == 0 { 1.6 } else { 0.65 };
+                let t = PI / 2.0 + i as f64 * PI / POINTS as f64;
+                (r * t.cos(), r * t.sin() - 0.1)
+            })
+            .collect();
+        vec![resample(&vertices, steps)]
+    }
+}
+
+/// A rose curve with `petals` petals.
+#[derive(Debug, Clone, Copy)]
+pub struct Rose {
+    /// Number of petals.
+    pub petals: u32,
+}
+
+impl Shape for Rose {
+    fn outlines(&self, steps: usize) -> Vec<Vec<Point>> {
+        // r = cos(k t) has k petals for odd k, |cos(k t)| has 2k petals for any k
+        let petals = f64::from(self.petals);
+        let odd = self.petals % 2 == 1;
+        vec![sample(steps, 0.0, TAU, |t| {
+            let r = if odd {
+                (petals * t).cos()
+            } else {
+                (petals / 2.0 * t).cos().abs()
+            };
+            (1.6 * r * t.cos(), 1.6 * r * t.sin())
+        })]
+    }
+}
+
+/// A closed polyline through user-supplied vertices.
+#[derive(Debug, Clone, Default)]
+pub struct Polyline {
+    /// Vertices in world coordinates, joined back to the first one.
+    pub vertices: Vec<Point>,
+}
+
+impl Shape for Polyline {
+    fn outlines(&self, steps: usize) -> Vec<Vec<Point>> {
+        vec![resample(&self.vertices, steps)]
+    }
+}
+
+/// Evaluate `f` at `steps + 1` evenly spaced parameters from `from` to `to`.
+fn sample(steps: usize, from: f64, to: f64, f: impl Fn(f64) -> Point) -> Vec<Point> {
+    (0..=steps)
+        .map(|i| f(from + (to - from) * i as f64 / steps as f64))
+        .collect()
+}
+
+/// Spread `steps` points evenly by length along the closed polygon `vertices`.
+fn resample(vertices: &[Point], steps: usize) -> Vec<Point> {
+    let Some(&first) = vertices.first() else {
+        return Vec::new();
+    };
+    let edges: Vec<(Point, Point)> = vertices
+        .iter()
+        .copied()
+        .zip(vertices.iter().copied().skip(1).chain([first]))
+        .collect();
+    let lengths: Vec<f64> = edges
+        .iter()
+        .map(|&((x0, y0), (x1, y1))| (x1 - x0).hypot(y1 - y0))
+        .collect();
+    let total: f64 = lengths.iter().sum();
+    if total == 0.0 {
+        return vec![first];
+    }
+
+    let mut points = Vec::with_capacity(steps + 1);
+    let (mut edge, mut start) = (0, 0.0);
+    for i in 0..=steps {
+        let target = total * i as f64 / steps as f64;
+        while edge + 1 < edges.len() && start + lengths[edge] < target {
+            start += lengths[edge];
+            edge += 1;
+        }
+        let ((x0, y0), (x1, y1)) = edges[edge];
+        let f = if lengths[edge] > 0.0 {
+            ((target - start) / lengths[edge]).clamp(0.0, 1.0)
+        } else {
+            0.0
+        };
+        points.push((x0 + (x1 - x0) * f, y0 + (y1 - y0) * f));
+    }
+    points
+}
+
+/// Scale, rotate (radians) and then translate a set of points.
+fn transform(points: &[Point], dx: f64, dy: f64, angle: f64, scale: f64) -> Vec<Point> {
+    let (sin, cos) = angle.sin_cos();
+    points
+        .iter()
+        .map(|&(x, y)| {
+            let (x, y) = (x * scale, y * scale);
+            (x * cos - y * sin + dx, x * sin + y * cos + dy)
+        })
+        .collect()
+}
@@ -0,0 +1,293 @@
+// src/solid.rs
+//! A solid 3D heart drawn by a small software renderer.
+//!
+//! The Taubin heart surface is sampled into a triangle mesh once. Every frame
+//! the mesh is turned, projected with perspective, lit and rasterized onto the
+//! canvas dot grid, keeping only the nearest surface at each dot.
+
+use std::f64::consts::{PI, TAU};
+
+use crate::{shape::Point, view::View};
+
+/// A point or direction in 3D: `x` to the right, `y` up and `z` towards the viewer.
+pub type Vec3 = [f64; 3];
+
+/// Distance from the camera to the centre of the heart, in world units.
+const CAMERA_DISTANCE: f64 = 6.0;
+/// Downward tilt of the camera, so the top of the heart shows as it turns.
+const TILT: f64 = 0.3;
+/// Direction the light comes from: above, left and in front.
+const LIGHT: Vec3 = [-0.45, 0.6, 0.66];
+/// Light that reaches surfaces facing away from the lamp.
+const AMBIENT: f64 = 0.2;
+
+/// Triangle mesh of a closed surface with a normal at every vertex.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Mesh {
+    positions: Vec<Vec3>,
+    normals: Vec<Vec3>,
+    triangles: Vec<[usize; 3]>,
+    /// Largest distance of a vertex from the origin.
+    radius: f64,
+}
+
+/// How the mesh sits in the world for one frame.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Pose {
+    /// Turn about the vertical axis in radians.
+    pub spin: f64,
+    /// Uniform scale, e.g. the heartbeat.
+    pub scale: f64,
+    /// Placement of the projected image on the canvas.
+    pub view: View,
+}
+
+/// One dot of the rendered surface.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Dot {
+    /// Dot centre in world coordinates.
+    pub point: Point,
+    /// Nearness of the surface, from `0` at the back of the heart to `1` at the front.
+    pub depth: f64,
+    /// Brightness of the lit surface from `0` to `1`.
+    pub light: f64,
+}
+
+impl Mesh {
+    /// The Taubin heart `(x² + 9/4 y² + z² - 1)³ - x² z³ - 9/80 y² z³ = 0`,
+    /// with `z` pointing up, sampled on `rings` x `segments` directions.
+    ///
+    /// Like [`crate::shape::ImplicitHeart`] the surface is star-shaped around
+    /// the origin, so each vertex is found by bisection along its ray.
+    pub fn heart(rings: usize, segments: usize) -> Self {
+        // Fit the default world like the flat hearts do
+        const SCALE: f64 = 1.3;
+        const LIFT: f64 = -0.1;
+
+        let (rings, segments) = (rings.max(2), segments.max(3));
+        let mut positions = Vec::with_capacity((rings + 1) * segments);
+        let mut normals = Vec::with_capacity(positions.capacity());
+        for ring in 0..=rings {
+            // From the top pole down to the bottom one
+            let theta = PI * ring as f64 / rings as f64;
+            for segment in 0..segments {
+                let phi = TAU * segment as f64 / segments as f64;
+                let dir = [
+                    theta.sin() * phi.cos(),
+                    theta.sin() * phi.sin(),
+                    theta.cos(),
+                ];
+                let surface = scale(dir, taubin_radius(dir));
+                let [x, y, z] = normalize(taubin_gradient(surface));
+                // Taubin's depth axis `y` becomes ours `z`, and its `z` is up
+                positions.push([
+                    SCALE * surface[0],
+                    SCALE * surface[2] + LIFT,
+                    SCALE * surface[1],
+                ]);
+                normals.push([x, z, y]);
+            }
+        }
+
+        let mut triangles = Vec::with_capacity(2 * rings * segments);
+        for ring in 0..rings {
+            for segment in 0..segments {
+                let next = (segment + 1) % segments;
+                let (a, b) = (ring * segments + segment, ring * segments + next);
+                let (c, d) = (a + segments, b + segments);
+                triangles.push([a, c, b]);
+                triangles.push([b, c, d]);
+            }
+        }
+
+        let radius = positions.iter().map(|&p| length(p)).fold(0.0, f64::max);
+        Self {
+            positions,
+            normals,
+            triangles,
+            radius,
+        }
+    }
+
+    /// Vertex positions in world units, before any pose.
+    pub fn positions(&self) -> &[Vec3] {
+        &self.positions
+    }
+
+    /// Number of triangles.
+    pub fn len(&self) -> usize {
+        self.triangles.len()
+    }
+
+    /// Whether the mesh has no triangles.
+    pub fn is_empty(&self) -> bool {
+        self.triangles.is_empty()
+    }
+
+    /// Render the mesh in `pose` onto a `cols` x `rows` dot grid spanning the
+    /// given bounds, returning the visible dots.
+    ///
+    /// Triangles are filled with interpolated depth and brightness, and a
+    /// depth buffer keeps the nearest surface at each dot.
+    pub fn render(
+        &self,
+        pose: &Pose,
+        x_bounds: [f64; 2],
+        y_bounds: [f64; 2],
+        cols: usize,
+        rows: usize,
+    ) -> Vec<Dot> {
+        if cols == 0 || rows == 0 {
+            return Vec::new();
+        }
+        let dx = (x_bounds[1] - x_bounds[0]) / cols as f64;
+        let dy = (y_bounds[1] - y_bounds[0]) / rows as f64;
+        let light = normalize(LIGHT);
+
+        // Grid position, nearness and brightness of every vertex
+        let depth_range = (self.radius * pose.scale).max(f64::EPSILON);
+        let vertices: Vec<(Point, f64, f64)> = self
+            .positions
+            .iter()
+            .zip(&self.normals)
+            .map(|(&position, &normal)| {
+                let [x, y, z] = scale(turn(position, pose.spin), pose.scale);
+                let perspective = CAMERA_DISTANCE / (CAMERA_DISTANCE - z);
+                let (wx, wy) = pose.view.apply((x * perspective, y * perspective));
+                let grid = ((wx - x_bounds[0]) / dx - 0.5, (y_bounds[1] - wy) / dy - 0.5);
+                let depth = (0.5 + 0.5 * z / depth_range).clamp(0.0, 1.0);
+                let shade = dot(turn(normal, pose.spin), light).max(0.0);
+                (grid, depth, AMBIENT + (1.0 - AMBIENT) * shade)
+            })
+            .collect();
+
+        // Nearness and brightness of the surface seen at each dot
+        let mut buffer: Vec<Option<(f64, f64)>> = vec![None; cols * rows];
+        for &[a, b, c] in &self.triangles {
+            rasterize(
+                [vertices[a], vertices[b], vertices[c]],
+                cols,
+                rows,
+                &mut buffer,
+            );
+        }
+
+        buffer
+            .iter()
+            .enumerate()
+            .filter_map(|(index, &sample)| {
+                let (depth, light) = sample?;
+                let (col, row) = (index % cols, index / cols);
+                let point = (
+                    x_bounds[0] + (col as f64 + 0.5) * dx,
+                    y_bounds[1] - (row as f64 + 0.5) * dy,
+                );
+                Some(Dot {
+                    point,
+                    depth,
+                    light,
+                })
+            })
+            .collect()
+    }
+}
+
+/// Fill one projected triangle into the depth buffer, keeping nearer samples.
+fn rasterize(
+    [(p0, d0, l0), (p1, d1, l1), (p2, d2, l2)]: [(Point, f64, f64); 3],
+    cols: usize,
+    rows: usize,
+    buffer: &mut [Option<(f64, f64)>],
+) {
+    let area = edge(p0, p1, p2);
+    if area.abs() < f64::EPSILON {
+        return;
+    }
+    let clamp = |v: f64, len: usize| v.clamp(0.0, len as f64 - 1.0) as usize;
+    let (left, right) = (p0.0.min(p1.0).min(p2.0), p0.0.max(p1.0).max(p2.0));
+    let (top, bottom) = (p0.1.min(p1.1).min(p2.1), p0.1.max(p1.1).max(p2.1));
+    if right < 0.0 || bottom < 0.0 || left > cols as f64 || top > rows as f64 {
+        return;
+    }
+
+    for row in clamp(top.ceil(), rows)..=clamp(bottom.floor(), rows) {
+        for col in clamp(left.ceil(), cols)..=clamp(right.floor(), cols) {
+            let p = (col as f64, row as f64);
+            let (w0, w1, w2) = (
+                edge(p1, p2, p) / area,
+                edge(p2, p0, p) / area,
+                edge(p0, p1, p) / area,
+            );
+            if w0 < 0.0 || w1 < 0.0 || w2 < 0.0 {
+                continue;
+            }
+            let depth = w0 * d0 + w1 * d1 + w2 * d2;
+            let slot = &mut buffer[row * cols + col];
+            if slot.is_none_or(|(nearest, _)| depth > nearest) {
+                *slot = Some((depth, w0 * l0 + w1 * l1 + w2 * l2));
+            }
+        }
+    }
+}
+
+/// Twice the signed area of the triangle `a`, `b`, `c`.
+fn edge(a: Point, b: Point, c: Point) -> f64 {
+    (b.0 - a.0) * (c.1 - a.1) - (b.1 - a.1) * (c.0 - a.0)
+}
+
+/// Turn `v` by `spin` about the vertical axis, then tilt it towards the camera.
+fn turn([x, y, z]: Vec3, spin: f64) -> Vec3 {
+    let (sin, cos) = spin.sin_cos();
+    let (x, z) = (x * cos + z * sin, z * cos - x * sin);
+    let (sin, cos) = TILT.sin_cos();
+    [x, y * cos - z * sin, z * cos + y * sin]
+}
+
+/// Distance from the origin to the Taubin surface in direction `dir`.
+fn taubin_radius(dir: Vec3) -> f64 {
+    let (mut lo, mut hi) = (0.0, 2.0);
+    for _ in 0..40 {
+        let mid = 0.5 * (lo + hi);
+        if taubin(scale(dir, mid)) < 0.0 {
+            lo = mid;
+        } else {
+            hi = mid;
+        }
+    }
+    0.5 * (lo + hi)
+}
+
+/// The Taubin heart function, negative inside the surface.
+fn taubin([x, y, z]: Vec3) -> f64 {
+    (x * x + 2.25 * y * y + z * z - 1.0).powi(3) - x * x * z.powi(3) - 0.1125 * y * y * z.powi(3)
+}
+
+/// Outward surface direction of [`taubin`] at `p`, by central differences.
+fn taubin_gradient(p: Vec3) -> Vec3 {
+    const H: f64 = 1e-4;
+    let mut gradient = [0.0; 3];
+    for (axis, slope) in gradient.iter_mut().enumerate() {
+        let (mut ahead, mut behind) = (p, p);
+        ahead[axis] += H;
+        behind[axis] -= H;
+        *slope = (taubin(ahead) - taubin(behind)) / (2.0 * H);
+    }
+    // The gradient vanishes at the cusps; point straight out there instead
+    if length(gradient) < 1e-9 { p } else { gradient }
+}
+
+fn scale([x, y, z]: Vec3, factor: f64) -> Vec3 {
+    [x * factor, y * factor, z * factor]
+}
+
+fn dot(a: Vec3, b: Vec3) -> f64 {
+    a[0] * b[0] + a[1] * b[1] + a[2] * b[2]
+}
+
+fn length(v: Vec3) -> f64 {
+    dot(v, v).sqrt()
+}
+
+fn normalize(v: Vec3) -> Vec3 {
+    scale(v, 1.0 / length(v).max(f64::EPSILON))
+}
@@ -0,0 +1,359 @@
+// src/sync.rs
+//! Keeping hearts on several terminals in step.
+//!
+//! One heart leads: every frame it sends a [`Beat`] with its animation clock,
+//! scene and view over localhost UDP to each follower that said hello in the
+//! last few seconds. Followers take on the leader's playback rate and pull
+//! their own clock towards the leader's, smoothly for small drift and with a
+//! jump for large gaps, so they stay in phase even when frames are late.
+//! Scenes and the view follow the leader too, so followers leave skipping and
+//! the mouse to it.
+//!
+//! With a [`Tile`] each terminal shows its part of one large heart.
+
+use std::{
+    net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr, UdpSocket},
+    str::FromStr,
+    time::{Duration, Instant},
+};
+
+use clap::ValueEnum;
+use color_eyre::{
+    Result,
+    eyre::{WrapErr, bail, ensure, eyre},
+};
+use ratatui::layout::Rect;
+use serde::{Deserialize, Deserializer, Serialize};
+
+use crate::{
+    board::Address,
+    clock::Clock,
+    config::Config,
+    render::{canvas_area, world_bounds},
+    scene::Playlist,
+    shape::Point,
+    view::View,
+};
+
+/// Default address the leader listens on.
+pub const DEFAULT_ADDRESS: SocketAddr = SocketAddr::new(IpAddr::V4(Ipv4Addr::LOCALHOST), 7878);
+/// Drift in animation seconds beyond which a follower jumps straight to the
+/// leader's time instead of catching up smoothly.
+pub const SNAP: f64 = 0.5;
+/// Supported number of tile columns and rows.
+pub const GRID_RANGE: std::ops::RangeInclusive<u16> = 1..=8;
+/// Seconds between a follower's hellos.
+const HELLO_INTERVAL: Duration = Duration::from_secs(1);
+/// Followers silent this long are dropped, and a leader silent this long is
+/// no longer followed.
+const TIMEOUT: Duration = Duration::from_secs(3);
+/// Share of the remaining drift a follower makes up per second.
+const GAIN: f64 = 4.0;
+/// What a follower sends to be sent beats.
+const HELLO: &[u8] = b"hello";
+
+/// Part a heart plays in a synchronized group.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Role {
+    /// Send the animation clock to the followers.
+    Lead,
+    /// Keep in step with a leader.
+    Follow,
+}
+
+/// What the leader shows at one moment.
+///
+/// The heartbeat follows from the animation time. The tick and palette
+/// position do too, but depend on the leader's frame rate and palette cycle,
+/// so they are sent as the leader sees them.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Beat {
+    /// Animation seconds.
+    pub seconds: f64,
+    /// Signed playback rate, zero while paused.
+    pub rate: f64,
+    /// Frame number at the leader's frame rate, negative before the start.
+    pub tick: i64,
+    /// Position in the palette, e.g. for [`crate::palette::Gradient::sample`].
+    pub palette: f64,
+    /// Animation seconds at which the leader's scenes started, moved by
+    /// skipping.
+    pub scenes: f64,
+    /// Where the user put the heart, before any [`Tile`] placement.
+    pub view: View,
+}
+
+impl Beat {
+    /// The state of `clock`, `playlist` and `view` with the leader's `config`.
+    pub fn new(clock: &Clock, config: &Config, playlist: &Playlist, view: &View) -> Self {
+        let seconds = clock.seconds();
+        Self {
+            seconds,
+            rate: clock.rate(),
+            tick: (seconds * config.fps).floor() as i64,
+            palette: config.phase(seconds),
+            scenes: playlist.offset(),
+            view: *view,
+        }
+    }
+}
+
+/// Parse a loopback UDP address, written like the message board's TCP ones:
+/// a port, `localhost:port` or a loopback `host:port`.
+pub fn parse_address(s: &str) -> Result<SocketAddr> {
+    match s.parse::<Address>()? {
+        Address::Tcp(addr) => Ok(addr),
+        #[cfg(unix)]
+        Address::Unix(path) => bail!(
+            "`{}` is a socket path, but hearts sync over UDP; give a port instead",
+            path.display()
+        ),
+    }
+}
+
+pub(crate) fn deserialize_address<'de, D: Deserializer<'de>>(
+    de: D,
+) -> Result<SocketAddr, D::Error> {
+    let s = String::deserialize(de)?;
+    parse_address(&s).map_err(|err| serde::de::Error::custom(format!("{err:#}")))
+}
+
+/// Move a follower's animation time `seconds` towards the leader's `target`
+/// over `dt` seconds of wall-clock time.
+///
+/// Small drift is made up gradually so the animation never visibly skips;
+/// beyond [`SNAP`] the follower jumps.
+pub fn correct(seconds: f64, target: f64, dt: f64) -> f64 {
+    let drift = target - seconds;
+    if drift.abs() > SNAP {
+        target
+    } else {
+        seconds + drift * (dt * GAIN).min(1.0)
+    }
+}
+
+/// Sends beats to the followers that asked for them.
+#[derive(Debug)]
+pub struct Leader {
+    socket: UdpSocket,
+    address: SocketAddr,
+    /// Followers and when they last said hello.
+    followers: Vec<(SocketAddr, Instant)>,
+}
+
+impl Leader {
+    /// Listen for followers on `address`.
+    pub fn bind(address: SocketAddr) -> Result<Self> {
+        let socket =
+            UdpSocket::bind(address).wrap_err_with(|| format!("failed to lead on {address}"))?;
+        socket.set_nonblocking(true)?;
+        Ok(Self {
+            address: socket.local_addr()?,
+            socket,
+            followers: Vec::new(),
+        })
+    }
+
+    /// Where followers say hello, with the port filled in when bound to port 0.
+    pub fn address(&self) -> SocketAddr {
+        self.address
+    }
+
+    /// Number of followers being sent beats.
+    pub fn followers(&self) -> usize {
+        self.followers.len()
+    }
+
+    /// Take in new followers, forget silent ones and send `beat` to the rest.
+    pub fn broadcast(&mut self, beat: &Beat, now: Instant) {
+        let mut buf = [0; 64];
+        while let Ok((len, from)) = self.socket.recv_from(&mut buf) {
+            if &buf[..len] != HELLO {
+                continue;
+            }
+            match self.followers.iter_mut().find(|(addr, _)| *addr == from) {
+                Some((_, seen)) => *seen = now,
+                None => self.followers.push((from, now)),
+            }
+        }
+        self.followers
+            .retain(|(_, seen)| now.saturating_duration_since(*seen) < TIMEOUT);
+
+        let Ok(datagram) = serde_json::to_vec(beat) else {
+            return;
+        };
+        for (addr, _) in &self.followers {
+            // A follower that has gone away is forgotten once its hellos stop
+            let _ = self.socket.send_to(&datagram, addr);
+        }
+    }
+}
+
+/// Keeps a clock in step with a leader.
+#[derive(Debug)]
+pub struct Follower {
+    socket: UdpSocket,
+    /// The latest beat and when it arrived.
+    beat: Option<(Beat, Instant)>,
+    /// When the last hello went out.
+    hello: Option<Instant>,
+    /// When the clock was last corrected.
+    synced: Option<Instant>,
+}
+
+impl Follower {
+    /// Follow the leader at `leader`, which need not be running yet.
+    pub fn connect(leader: SocketAddr) -> Result<Self> {
+        let local = match leader {
+            SocketAddr::V4(_) => IpAddr::V4(Ipv4Addr::LOCALHOST),
+            SocketAddr::V6(_) => IpAddr::V6(Ipv6Addr::LOCALHOST),
+        };
+        let socket = UdpSocket::bind((local, 0))?;
+        socket
+            .connect(leader)
+            .wrap_err_with(|| format!("failed to follow {leader}"))?;
+        socket.set_nonblocking(true)?;
+        Ok(Self {
+            socket,
+            beat: None,
+            hello: None,
+            synced: None,
+        })
+    }
+
+    /// Whether a beat arrived recently enough to follow.
+    pub fn is_locked(&self, now: Instant) -> bool {
+        self.beat
+            .is_some_and(|(_, at)| now.saturating_duration_since(at) < TIMEOUT)
+    }
+
+    /// The leader's latest beat, while it is recent enough to follow.
+    pub fn beat(&self, now: Instant) -> Option<Beat> {
+        self.beat
+            .filter(|_| self.is_locked(now))
+            .map(|(beat, _)| beat)
+    }
+
+    /// Read the leader's beats, say hello when due and pull `clock` towards
+    /// the leader's time at `now`.
+    ///
+    /// Returns how many animation seconds the correction moved the clock.
+    /// Without a leader the clock runs on by itself.
+    pub fn sync(&mut self, clock: &mut Clock, now: Instant) -> f64 {
+        let mut buf = [0; 1024];
+        while let Ok(len) = self.socket.recv(&mut buf) {
+            if let Ok(beat) = serde_json::from_slice::<Beat>(&buf[..len]) {
+                self.beat = Some((beat, now));
+            }
+        }
+        if self
+            .hello
+            .is_none_or(|at| now.saturating_duration_since(at) >= HELLO_INTERVAL)
+        {
+            // Refused until the leader is up; the next hello tries again
+            let _ = self.socket.send(HELLO);
+            self.hello = Some(now);
+        }
+
+        let dt = self.synced.replace(now).map_or(0.0, |last| {
+            now.saturating_duration_since(last).as_secs_f64()
+        });
+        let Some((beat, at)) = self.beat.filter(|_| self.is_locked(now)) else {
+            return 0.0;
+        };
+        clock.lock(beat.rate);
+        let target = beat.seconds + beat.rate * now.saturating_duration_since(at).as_secs_f64();
+        clock.seek(correct(clock.seconds(), target, dt))
+    }
+}
+
+/// This terminal's place in a grid of terminals showing one large heart.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Tile {
+    /// Terminals across the grid.
+    pub columns: u16,
+    /// Terminals down the grid.
+    pub rows: u16,
+    /// This terminal's column, from `0` on the left.
+    pub column: u16,
+    /// This terminal's row, from `0` at the top.
+    pub row: u16,
+}
+
+impl FromStr for Tile {
+    type Err = color_eyre::Report;
+
+    /// Parse `COLUMNSxROWS@COLUMN,ROW`, e.g. `2x1@1,0` for the right half.
+    fn from_str(s: &str) -> Result<Self> {
+        let invalid = || eyre!("`{s}` is not a tile like 2x1@0,0");
+        let (grid, place) = s.trim().split_once('@').ok_or_else(invalid)?;
+        let (columns, rows) = grid.split_once(['x', 'X']).ok_or_else(invalid)?;
+        let (column, row) = place.split_once(',').ok_or_else(invalid)?;
+        let number = |n: &str| n.trim().parse::<u16>().map_err(|_| invalid());
+        let tile = Tile {
+            columns: number(columns)?,
+            rows: number(rows)?,
+            column: number(column)?,
+            row: number(row)?,
+        };
+        ensure!(
+            GRID_RANGE.contains(&tile.columns) && GRID_RANGE.contains(&tile.rows),
+            "a {}x{} grid is outside the supported range {GRID_RANGE:?}",
+            tile.columns,
+            tile.rows
+        );
+        ensure!(
+            tile.column < tile.columns && tile.row < tile.rows,
+            "tile {},{} is outside the {}x{} grid",
+            tile.column,
+            tile.row,
+            tile.columns,
+            tile.rows
+        );
+        Ok(tile)
+    }
+}
+
+impl<'de> Deserialize<'de> for Tile {
+    fn deserialize<D: Deserializer<'de>>(de: D) -> Result<Self, D::Error> {
+        let s = String::deserialize(de)?;
+        s.parse()
+            .map_err(|err| serde::de::Error::custom(format!("{err:#}")))
+    }
+}
+
+impl Tile {
+    /// `view` of the heart across the whole grid, as seen by this terminal
+    /// drawing into `area`.
+    ///
+    /// Every terminal is assumed to be the same size. The heart grows with
+    /// the shorter side of the grid and is centred on the middle of the grid.
+    pub fn place(&self, view: &View, area: Rect, config: &Config) -> View {
+        let (zoom, (dx, dy)) = self.shift(area, config);
+        View {
+            rotation: view.rotation,
+            offset: (view.offset.0 * zoom - dx, view.offset.1 * zoom - dy),
+            zoom: view.zoom * zoom,
+        }
+    }
+
+    /// World point `at` on this terminal's canvas in `area`, in the world of
+    /// the heart across the whole grid, where [`Tile::place`] took the view
+    /// from.
+    pub fn to_grid(&self, at: Point, area: Rect, config: &Config) -> Point {
+        let (zoom, (dx, dy)) = self.shift(area, config);
+        ((at.0 + dx) / zoom, (at.1 + dy) / zoom)
+    }
+
+    /// How much the grid's heart is scaled up on each terminal, and how far
+    /// this tile's centre lies from the middle of the grid in world units.
+    fn shift(&self, area: Rect, config: &Config) -> (f64, Point) {
+        let ([_, x], [_, y]) = world_bounds(canvas_area(area, config), config);
+        let zoom = f64::from(self.columns.min(self.rows));
+        // This tile's centre seen from the middle of the grid is (cx, cy)
+        let cx = f64::from(2 * self.column + 1) - f64::from(self.columns);
+        let cy = f64::from(self.rows) - f64::from(2 * self.row + 1);
+        (zoom, (cx * x, cy * y))
+    }
+}
@@ -0,0 +1,119 @@
+// src/tui.rs
+//! Terminal setup and teardown that also holds on panics and termination signals.
+
+use std::{
+    io::{self, Stdout},
+    panic,
+    sync::{
+        Arc,
+        atomic::{AtomicBool, Ordering},
+    },
+};
+
+use color_eyre::{Result, config::HookBuilder};
+use crossterm::{
+    cursor::{Hide, Show},
+    event::{DisableMouseCapture, EnableMouseCapture},
+    execute,
+    terminal::{EnterAlternateScreen, LeaveAlternateScreen, disable_raw_mode, enable_raw_mode},
+};
+use ratatui::{Terminal, backend::CrosstermBackend};
+
+/// The terminal the heart is drawn on.
+pub type Tui = Terminal<CrosstermBackend<Stdout>>;
+
+/// Whether [`init`] took over the terminal and [`restore`] has yet to give it back.
+static ENTERED: AtomicBool = AtomicBool::new(false);
+
+/// Install `color_eyre` with a panic hook that puts the terminal back to normal
+/// before the panic report is printed.
+///
+/// Errors need no such hook: reports are created while the app is still
+/// running (e.g. for the config overlay) and `main` restores the terminal
+/// before returning one.
+pub fn install_hooks() -> Result<()> {
+    let (panic_hook, eyre_hook) = HookBuilder::default().into_hooks();
+    eyre_hook.install()?;
+
+    let panic_hook = panic_hook.into_panic_hook();
+    panic::set_hook(Box::new(move |info| {
+        let _ = restore();
+        panic_hook(info);
+    }));
+    Ok(())
+}
+
+/// Switch to raw mode on the alternate screen with the cursor hidden.
+pub fn init() -> Result<Tui> {
+    enable_raw_mode()?;
+    ENTERED.store(true, Ordering::SeqCst);
+    let mut stdout = io::stdout();
+    if let Err(err) = execute!(stdout, EnterAlternateScreen, Hide) {
+        let _ = restore();
+        return Err(err.into());
+    }
+    Ok(Terminal::new(CrosstermBackend::new(stdout))?)
+}
+
+/// Start or stop receiving mouse events instead of leaving them to the terminal.
+pub fn capture_mouse(capture: bool) -> Result<()> {
+    if capture {
+        execute!(io::stdout(), EnableMouseCapture)?;
+    } else {
+        execute!(io::stdout(), DisableMouseCapture)?;
+    }
+    Ok(())
+}
+
+/// Release the mouse, leave the alternate screen, show the cursor and disable
+/// raw mode.
+///
+/// Safe to call more than once. Does nothing when [`init`] never ran, so a
+/// panic in a headless run or export writes no escape codes to stdout.
+pub fn restore() -> Result<()> {
+    if !ENTERED.swap(false, Ordering::SeqCst) {
+        return Ok(());
+    }
+    execute!(
+        io::stdout(),
+        DisableMouseCapture,
+        LeaveAlternateScreen,
+        Show
+    )?;
+    disable_raw_mode()?;
+    Ok(())
+}
+
+/// Records whether the process was asked to terminate.
+///
+/// Raw mode turns Ctrl-C into a key press, so this covers `SIGTERM`, `SIGHUP`
+/// and an explicit `SIGINT` from another process.
+#[derive(Debug, Clone, Default)]
+pub struct Signals {
+    received: Arc<AtomicBool>,
+}
+
+impl Signals {
+    /// Start listening for termination signals.
+    #[cfg(unix)]
+    pub fn register() -> Result<Self> {
+        use signal_hook::consts::{SIGHUP, SIGINT, SIGTERM};
+
+        let signals = Self::default();
+        for signal in [SIGTERM, SIGHUP, SIGINT] {
+            signal_hook::flag::register(signal, Arc::clone(&signals.received))?;
+        }
+        Ok(signals)
+    }
+
+    /// Start listening for termination signals.
+    #[cfg(not(unix))]
+    pub fn register() -> Result<Self> {
+        Ok(Self::default())
+    }
+
+    /// Whether a termination signal has arrived.
+    pub fn received(&self) -> bool {
+        self.received.load(Ordering::Relaxed)
+    }
+}
@@ -0,0 +1,66 @@
+// src/view.rs
+//! Where the heart sits on the canvas: moved, turned and zoomed with the mouse.
+
+use std::ops::RangeInclusive;
+
+use serde::{Deserialize, Serialize};
+
+use crate::shape::Point;
+
+/// Supported zoom factors.
+pub const ZOOM_RANGE: RangeInclusive<f64> = 0.25..=4.0;
+
+/// Transform from shape coordinates to canvas world coordinates.
+///
+/// Shapes are rotated about their own origin, scaled by `zoom` and then moved
+/// by `offset`. Particles are not transformed: they already live in world
+/// coordinates.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct View {
+    /// Counter-clockwise rotation in radians.
+    pub rotation: f64,
+    /// Where the shape's origin ends up in world coordinates.
+    pub offset: Point,
+    /// Scale factor, within [`ZOOM_RANGE`].
+    pub zoom: f64,
+}
+
+impl Default for View {
+    fn default() -> Self {
+        Self {
+            rotation: 0.0,
+            offset: (0.0, 0.0),
+            zoom: 1.0,
+        }
+    }
+}
+
+impl View {
+    /// Map a point of the shape to world coordinates.
+    pub fn apply(&self, (x, y): Point) -> Point {
+        let (sin, cos) = self.rotation.sin_cos();
+        (
+            (x * cos - y * sin) * self.zoom + self.offset.0,
+            (x * sin + y * cos) * self.zoom + self.offset.1,
+        )
+    }
+
+    /// Move the shape as if dragged from world point `from` to `to`.
+    pub fn translate(&mut self, from: Point, to: Point) {
+        self.offset.0 += to.0 - from.0;
+        self.offset.1 += to.1 - from.1;
+    }
+
+    /// Turn the shape about its centre as if dragged from world point `from`
+    /// to `to`.
+    pub fn rotate(&mut self, from: Point, to: Point) {
+        let angle = |(x, y): Point| (y - self.offset.1).atan2(x - self.offset.0);
+        let turn = angle(to) - angle(from);
+        self.rotation = (self.rotation + turn).rem_euclid(std::f64::consts::TAU);
+    }
+
+    /// Multiply the zoom by `factor`, staying within [`ZOOM_RANGE`].
+    pub fn zoom_by(&mut self, factor: f64) {
+        self.zoom = (self.zoom * factor).clamp(*ZOOM_RANGE.start(), *ZOOM_RANGE.end());
+    }
+}
@@ -0,0 +1,222 @@
+// src/widget.rs
+//! The heart as a ratatui widget, for embedding in other layouts.
+//!
+//! ```no_run
+//! # use ratatui::Frame;
+//! # use ratatui_heart::{shape::ShapeKind, widget::{HeartState, HeartWidget}};
+//! # fn draw(frame: &mut Frame, state: &mut HeartState, seconds: f64) {
+//! let heart = HeartWidget::new()
+//!     .shape(ShapeKind::Rose)
+//!     .palette("sunset")
+//!     .thickness(0.08)
+//!     .seconds(seconds);
+//! heart.update(state, 1.0 / 12.5);
+//! frame.render_stateful_widget(heart, frame.area(), state);
+//! # }
+//! ```
+
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::Color,
+    widgets::{StatefulWidget, Widget},
+};
+
+use crate::{
+    config::Config,
+    palette::ColorDepth,
+    particles::Particles,
+    render::{self, Geometry},
+    shape::ShapeKind,
+    view::View,
+};
+
+/// The animated heart at one moment, with the settings it is drawn with.
+///
+/// Built like other ratatui widgets: start from [`HeartWidget::new`] or a
+/// whole [`Config`], then adjust it with the builder methods. Rendered as a
+/// plain [`Widget`] it has no particles; as a [`StatefulWidget`] it keeps its
+/// outlines and particles in a [`HeartState`] between frames.
+#[derive(Debug, Clone, PartialEq)]
+#[must_use]
+pub struct HeartWidget {
+    config: Config,
+    seconds: f64,
+    view: View,
+}
+
+impl Default for HeartWidget {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl HeartWidget {
+    /// A heart with the default settings at the start of the animation.
+    pub fn new() -> Self {
+        Self {
+            config: Config {
+                color_depth: ColorDepth::Auto.resolve(),
+                ..Config::default()
+            },
+            seconds: 0.0,
+            view: View::default(),
+        }
+    }
+
+    /// Replace every setting with `config`, detecting an `auto` color depth
+    /// from the environment once here instead of on every frame.
+    pub fn config(mut self, mut config: Config) -> Self {
+        config.color_depth = config.color_depth.resolve();
+        self.config = config;
+        self
+    }
+
+    /// Outline to draw.
+    pub fn shape(mut self, shape: ShapeKind) -> Self {
+        self.config.shape = shape;
+        self
+    }
+
+    /// Built-in or user-defined palette to cycle through.
+    pub fn palette(mut self, palette: impl Into<String>) -> Self {
+        self.config.palette = palette.into();
+        self.config.colors.clear();
+        self
+    }
+
+    /// Colors to cycle through instead of the palette.
+    pub fn colors(mut self, colors: impl IntoIterator<Item = Color>) -> Self {
+        self.config.colors = colors.into_iter().collect();
+        self
+    }
+
+    /// Spacing between outline layers in world units.
+    pub fn thickness(mut self, thickness: f64) -> Self {
+        self.config.thickness = thickness;
+        self
+    }
+
+    /// Number of outline layers.
+    pub fn layers(mut self, layers: u16) -> Self {
+        self.config.layers = layers;
+        self
+    }
+
+    /// Fill the inside of the shape.
+    pub fn filled(mut self, filled: bool) -> Self {
+        self.config.filled = filled;
+        self
+    }
+
+    /// Draw the solid 3D heart instead of the flat shape.
+    pub fn solid(mut self, solid: bool) -> Self {
+        self.config.solid = solid;
+        self
+    }
+
+    /// Message shown with the heart.
+    pub fn message(mut self, message: impl Into<String>) -> Self {
+        self.config.message = Some(message.into());
+        self
+    }
+
+    /// Animation time in seconds, which drives the colors, the heartbeat and
+    /// the message effects.
+    pub fn seconds(mut self, seconds: f64) -> Self {
+        self.seconds = seconds;
+        self
+    }
+
+    /// Where the heart sits on the canvas.
+    pub fn view(mut self, view: View) -> Self {
+        self.view = view;
+        self
+    }
+
+    /// The settings the heart is drawn with.
+    pub fn settings(&self) -> &Config {
+        &self.config
+    }
+
+    /// Step the particles in `state` by `dt` seconds up to this widget's time,
+    /// within the canvas it was last drawn on.
+    pub fn update(&self, state: &mut HeartState, dt: f64) {
+        let bounds = state.world_bounds(&self.config);
+        state
+            .particles
+            .update(&self.config, &self.view, bounds, self.seconds, dt);
+    }
+}
+
+/// What a [`HeartWidget`] keeps between frames: cached outlines and the
+/// particles around the heart.
+#[derive(Debug, Clone, PartialEq)]
+pub struct HeartState {
+    geometry: Geometry,
+    particles: Particles,
+    /// World bounds of the canvas last drawn on.
+    bounds: Option<([f64; 2], [f64; 2])>,
+}
+
+impl HeartState {
+    /// A fresh state whose particles follow `seed`.
+    pub fn new(seed: u64) -> Self {
+        Self {
+            geometry: Geometry::default(),
+            particles: Particles::new(seed),
+            bounds: None,
+        }
+    }
+
+    /// World bounds of the canvas the heart was last drawn on, or the square
+    /// `[-bounds, bounds]` of `config` before the first frame.
+    pub fn world_bounds(&self, config: &Config) -> ([f64; 2], [f64; 2]) {
+        let square = [-config.bounds, config.bounds];
+        self.bounds.unwrap_or((square, square))
+    }
+
+    /// The particles, e.g. to throw confetti.
+    pub fn particles_mut(&mut self) -> &mut Particles {
+        &mut self.particles
+    }
+}
+
+impl Default for HeartState {
+    fn default() -> Self {
+        Self::new(0)
+    }
+}
+
+impl StatefulWidget for HeartWidget {
+    type State = HeartState;
+
+    fn render(self, area: Rect, buf: &mut Buffer, state: &mut HeartState) {
+        StatefulWidget::render(&self, area, buf, state);
+    }
+}
+
+impl StatefulWidget for &HeartWidget {
+    type State = HeartState;
+
+    fn render(self, area: Rect, buf: &mut Buffer, state: &mut HeartState) {
+        let canvas = render::canvas_area(area, &self.config);
+        state.bounds = Some(render::world_bounds(canvas, &self.config));
+        render::draw_heart(
+            buf,
+            area,
+            &self.config,
+            &mut state.geometry,
+            &self.view,
+            &state.particles,
+            self.seconds,
+        );
+    }
+}
+
+impl Widget for HeartWidget {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let mut state = HeartState::new(self.config.seed.unwrap_or(0));
+        StatefulWidget::render(self, area, buf, &mut state);
+    }
+}
@@ -0,0 +1,284 @@
+//! The message board: addresses, notes, the queue and the socket listener.
+
+use std::{
+    io::{BufRead, BufReader, Write},
+    net::TcpStream,
+    sync::mpsc,
+    thread,
+    time::{Duration, Instant},
+};
+
+use ratatui::{Terminal, backend::TestBackend, style::Color};
+use ratatui_heart::{
+    board::{Address, Board, Listener, MAX_CLIENTS, MAX_QUEUE, Note, NoteEffect, serve},
+    config::Config,
+    palette::ColorDepth,
+    render::draw_note,
+};
+
+fn note(text: &str) -> Note {
+    format!(r#"{{"text": "{text}"}}"#).parse().unwrap()
+}
+
+/// Wait up to a second for `listener` to hand over a note.
+fn receive(listener: &Listener) -> Note {
+    let deadline = Instant::now() + Duration::from_secs(1);
+    loop {
+        if let Some(note) = listener.notes().next() {
+            return note;
+        }
+        assert!(Instant::now() < deadline, "no note arrived");
+        thread::sleep(Duration::from_millis(10));
+    }
+}
+
+#[test]
+fn addresses_stay_on_this_machine() {
+    let tcp = |s: &str| match s.parse::<Address>().unwrap() {
+        Address::Tcp(addr) => addr.to_string(),
+        other => panic!("{other} is not TCP"),
+    };
+    assert_eq!(tcp("7777"), "127.0.0.1:7777");
+    assert_eq!(tcp("localhost:7777"), "127.0.0.1:7777");
+    assert_eq!(tcp("[::1]:7777"), "[::1]:7777");
+    assert!("0.0.0.0:7777".parse::<Address>().is_err());
+    assert!("192.168.1.2:7777".parse::<Address>().is_err());
+    assert!("heart".parse::<Address>().is_err());
+}
+
+#[test]
+fn notes_parse_from_json_lines() {
+    let full: Note =
+        r##"{"text": "Be mine", "from": "Sam", "color": "#ff0066", "effect": "burst", "duration": "3s"}"##
+            .parse()
+            .unwrap();
+    assert_eq!(full.text, "Be mine");
+    assert_eq!(full.from.as_deref(), Some("Sam"));
+    assert_eq!(full.color, Some(Color::Rgb(255, 0, 102)));
+    assert_eq!(full.effect, NoteEffect::Burst);
+    assert_eq!(full.duration, Some(Duration::from_secs(3)));
+
+    let plain = note("hi");
+    assert_eq!(plain.effect, NoteEffect::None);
+    assert_eq!((plain.color, plain.duration), (None, None));
+}
+
+#[test]
+fn bad_notes_are_rejected() {
+    for line in [
+        r#"{"text": ""}"#,
+        r#"{"text": "hi", "colour": "red"}"#,
+        r#"{"text": "hi", "effect": "explode"}"#,
+        r#"{"text": "hi", "duration": "0s"}"#,
+        r#"{"from": "Sam"}"#,
+        "not json",
+    ] {
+        assert!(line.parse::<Note>().is_err(), "{line} was accepted");
+    }
+    let long = format!(r#"{{"text": "{}"}}"#, "x".repeat(281));
+    assert!(long.parse::<Note>().is_err());
+}
+
+#[test]
+fn control_characters_never_reach_the_terminal() {
+    let note: Note = r#"{"text": "a\u001b[2Jb\nc\td", "from": "e\u0007f"}"#
+        .parse()
+        .unwrap();
+    assert_eq!(note.text, "a[2Jb\ncd");
+    assert_eq!(note.from.as_deref(), Some("ef"));
+}
+
+#[test]
+fn notes_take_turns_for_their_display_time() {
+    let mut board = Board::new();
+    let default = Duration::from_secs(5);
+    assert!(board.is_idle());
+    board.push(note("one"));
+    board.push(r#"{"text": "two", "duration": "1s"}"#.parse().unwrap());
+    board.push(note("three"));
+
+    assert_eq!(board.update(0.1, default).unwrap().text, "one");
+    assert_eq!(board.waiting(), 2);
+    assert!(board.update(4.8, default).is_none());
+    assert_eq!(board.current().unwrap().0.text, "one");
+    assert_eq!(board.update(0.2, default).unwrap().text, "two");
+    assert_eq!(board.update(1.0, default).unwrap().text, "three");
+    assert!(board.update(5.0, default).is_none());
+    assert!(board.is_idle());
+}
+
+#[test]
+fn a_full_queue_turns_notes_away() {
+    let mut board = Board::new();
+    for _ in 0..MAX_QUEUE {
+        assert!(board.push(note("hi")));
+    }
+    assert!(!board.push(note("one too many")));
+    assert_eq!(board.waiting(), MAX_QUEUE);
+}
+
+#[test]
+fn notes_show_in_a_box_near_the_top() {
+    let config = Config {
+        color_depth: ColorDepth::TrueColor,
+        ..Config::default()
+    };
+    let note: Note = r##"{"text": "Roses are red, violets are blue", "from": "Sam", "color": "#ff0066", "effect": "typewriter"}"##
+        .parse()
+        .unwrap();
+    let mut terminal = Terminal::new(TestBackend::new(24, 8)).unwrap();
+    terminal
+        .draw(|f| draw_note(f, &note, Color::White, 1.0, &config))
+        .unwrap();
+    let buf = terminal.backend().buffer();
+    let row = |y: u16| (0..24).map(|x| buf[(x, y)].symbol()).collect::<String>();
+    assert!(row(0).trim().is_empty());
+    assert!(row(1).contains("from Sam"));
+    // Ten characters typed after a second, wrapped at the box edge
+    assert_eq!(row(2), " │ Roses are          │ ");
+    assert!(row(3).trim_matches([' ', '│']).is_empty());
+    assert_eq!(buf[(3, 2)].fg, Color::Rgb(255, 0, 102));
+}
+
+#[test]
+fn every_line_gets_an_answer() {
+    let (sender, notes) = mpsc::sync_channel(MAX_QUEUE);
+    let input = "{\"text\": \"hi\"}\n\nnope\n{\"text\": \"bye\"}\n";
+    let mut replies = Vec::new();
+    serve(input.as_bytes(), &mut replies, &sender).unwrap();
+    let replies = String::from_utf8(replies).unwrap();
+    let replies: Vec<&str> = replies.lines().collect();
+    assert_eq!(replies.len(), 3);
+    assert_eq!(replies[0], "ok");
+    assert!(replies[1].starts_with("error: "));
+    assert_eq!(replies[2], "ok");
+    let texts: Vec<String> = notes.try_iter().map(|note| note.text).collect();
+    assert_eq!(texts, ["hi", "bye"]);
+}
+
+#[test]
+fn overlong_lines_end_the_connection() {
+    let (sender, notes) = mpsc::sync_channel(MAX_QUEUE);
+    let input = format!("{}\n{{\"text\": \"hi\"}}\n", "x".repeat(5000));
+    let mut replies = Vec::new();
+    serve(input.as_bytes(), &mut replies, &sender).unwrap();
+    assert!(String::from_utf8(replies).unwrap().starts_with("error: "));
+    assert!(notes.try_iter().next().is_none());
+}
+
+#[test]
+fn notes_without_room_are_refused() {
+    let (sender, notes) = mpsc::sync_channel(1);
+    let input = "{\"text\": \"one\"}\n{\"text\": \"two\"}\n";
+    let mut replies = Vec::new();
+    serve(input.as_bytes(), &mut replies, &sender).unwrap();
+    assert_eq!(
+        String::from_utf8(replies).unwrap(),
+        "ok\nerror: board is full\n"
+    );
+    assert_eq!(notes.try_iter().count(), 1);
+}
+
+#[test]
+fn notes_wait_in_the_listener_until_the_board_has_room() {
+    let listener = Listener::bind(&"127.0.0.1:0".parse().unwrap()).unwrap();
+    let Address::Tcp(addr) = listener.address() else {
+        panic!("not bound to TCP");
+    };
+    let mut board = Board::new();
+    for _ in 1..MAX_QUEUE {
+        board.push(note("hi"));
+    }
+    let mut stream = TcpStream::connect(addr).unwrap();
+    stream
+        .write_all(b"{\"text\": \"one\"}\n{\"text\": \"two\"}\n")
+        .unwrap();
+    let mut reader = BufReader::new(&stream);
+    for _ in 0..2 {
+        let mut reply = String::new();
+        reader.read_line(&mut reply).unwrap();
+        assert_eq!(reply, "ok\n");
+    }
+    listener.deliver(&mut board);
+    assert!(board.is_full());
+    assert_eq!(receive(&listener).text, "two");
+}
+
+#[test]
+fn too_many_clients_are_turned_away() {
+    let listener = Listener::bind(&"127.0.0.1:0".parse().unwrap()).unwrap();
+    let Address::Tcp(addr) = listener.address() else {
+        panic!("not bound to TCP");
+    };
+    let mut clients: Vec<TcpStream> = (0..MAX_CLIENTS)
+        .map(|_| TcpStream::connect(addr).unwrap())
+        .collect();
+    // Each of them is being served
+    for client in &mut clients {
+        client.write_all(b"{\"text\": \"hi\"}\n").unwrap();
+        let mut reply = String::new();
+        BufReader::new(&*client).read_line(&mut reply).unwrap();
+        assert_eq!(reply, "ok\n");
+    }
+    let extra = TcpStream::connect(addr).unwrap();
+    let mut reply = String::new();
+    BufReader::new(&extra).read_line(&mut reply).unwrap();
+    assert_eq!(reply, "error: too many clients\n");
+}
+
+#[test]
+fn notes_arrive_over_tcp() {
+    let listener = Listener::bind(&"127.0.0.1:0".parse().unwrap()).unwrap();
+    let Address::Tcp(addr) = listener.address() else {
+        panic!("not bound to TCP");
+    };
+    assert_ne!(addr.port(), 0);
+
+    let mut stream = TcpStream::connect(addr).unwrap();
+    stream
+        .write_all(b"{\"text\": \"Be mine\", \"effect\": \"confetti\"}\n")
+        .unwrap();
+    let mut reply = String::new();
+    BufReader::new(&stream).read_line(&mut reply).unwrap();
+    assert_eq!(reply, "ok\n");
+    let note = receive(&listener);
+    assert_eq!(note.text, "Be mine");
+    assert_eq!(note.effect, NoteEffect::Confetti);
+}
+
+#[cfg(unix)]
+#[test]
+fn notes_arrive_over_a_unix_socket() {
+    use std::os::unix::net::{UnixListener, UnixStream};
+
+    assert_eq!(
+        "unix:heart.sock".parse::<Address>().unwrap(),
+        Address::Unix("heart.sock".into())
+    );
+
+    let path = std::env::temp_dir().join(format!("ratatui_heart-{}.sock", std::process::id()));
+    // A stale socket from an earlier run is replaced
+    drop(UnixListener::bind(&path).unwrap());
+    assert!(path.exists());
+    let address = Address::Unix(path.clone());
+    let listener = Listener::bind(&address).unwrap();
+    assert!(Listener::bind(&address).is_err(), "the socket is taken");
+
+    let mut stream = UnixStream::connect(&path).unwrap();
+    stream.write_all(b"{\"text\": \"hi\"}\n").unwrap();
+    assert_eq!(receive(&listener).text, "hi");
+
+    drop(listener);
+    assert!(!path.exists(), "the socket is cleaned up");
+}
+
+#[cfg(unix)]
+#[test]
+fn other_files_are_not_replaced_by_the_socket() {
+    let path = std::env::temp_dir().join(format!("ratatui_heart-{}.txt", std::process::id()));
+    std::fs::write(&path, "keep me").unwrap();
+    let err = Listener::bind(&Address::Unix(path.clone())).unwrap_err();
+    assert!(err.to_string().contains("is not a socket"), "{err}");
+    assert_eq!(std::fs::read_to_string(&path).unwrap(), "keep me");
+    std::fs::remove_file(&path).unwrap();
+}
@@ -0,0 +1,90 @@
+//! Playback controls of the animation clock.
+
+use std::time::{Duration, Instant};
+
+use ratatui_heart::clock::{Clock, SPEED_RANGE};
+
+const SECOND: Duration = Duration::from_secs(1);
+
+/// A clock whose last update was at the returned instant, with the animation
+/// time it showed then.
+fn clock() -> (Clock, Instant, f64) {
+    let mut clock = Clock::new(10.0);
+    let now = Instant::now();
+    clock.update_at(now);
+    let start = clock.seconds();
+    (clock, now, start)
+}
+
+fn assert_close(actual: f64, expected: f64) {
+    assert!((actual - expected).abs() < 1e-9, "{actual} != {expected}");
+}
+
+#[test]
+fn follows_real_time_at_the_chosen_speed() {
+    let (mut clock, now, start) = clock();
+    assert_close(clock.update_at(now + SECOND), 1.0);
+
+    clock.faster();
+    clock.update_at(now + 2 * SECOND);
+    assert_close(clock.seconds() - start, 3.0);
+
+    clock.slower();
+    clock.slower();
+    clock.update_at(now + 3 * SECOND);
+    assert_close(clock.seconds() - start, 3.5);
+    assert_eq!(clock.status().as_deref(), Some("0.5x"));
+}
+
+#[test]
+fn pause_stops_time_and_step_moves_one_frame() {
+    let (mut clock, now, start) = clock();
+    clock.toggle_pause();
+    assert_eq!(clock.update_at(now + SECOND), 0.0);
+    assert_eq!(clock.status().as_deref(), Some("paused"));
+
+    clock.step(Duration::from_millis(80));
+    assert_close(clock.seconds() - start, 0.08);
+
+    clock.toggle_pause();
+    clock.update_at(now + 2 * SECOND);
+    assert_close(clock.seconds() - start, 1.08);
+}
+
+#[test]
+fn reverse_runs_time_backwards() {
+    let (mut clock, now, start) = clock();
+    clock.reverse();
+    assert_close(clock.update_at(now + 2 * SECOND), -2.0);
+    assert_close(clock.seconds() - start, -2.0);
+    assert_close(clock.step(SECOND), -1.0);
+    assert_eq!(clock.status().as_deref(), Some("paused reverse"));
+}
+
+#[test]
+fn speed_stays_in_range() {
+    let (mut clock, ..) = clock();
+    for _ in 0..10 {
+        clock.faster();
+    }
+    assert_eq!(clock.rate(), *SPEED_RANGE.end());
+    for _ in 0..20 {
+        clock.slower();
+    }
+    assert_eq!(clock.rate(), *SPEED_RANGE.start());
+}
+
+#[test]
+fn seek_jumps_and_lock_takes_on_another_rate() {
+    let (mut clock, now, start) = clock();
+    assert_close(clock.seek(start + 5.0), 5.0);
+    assert_close(clock.seconds() - start, 5.0);
+
+    clock.lock(-2.0);
+    assert_close(clock.rate(), -2.0);
+    assert_close(clock.update_at(now + SECOND), -2.0);
+    clock.lock(0.0);
+    assert_eq!(clock.status().as_deref(), Some("paused reverse 2x"));
+    clock.lock(100.0);
+    assert_eq!(clock.rate(), *SPEED_RANGE.end());
+}
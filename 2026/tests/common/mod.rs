@@ -0,0 +1,12 @@
+//! Helpers shared by the integration tests.
+
+use ratatui_heart::{config::Config, palette::ColorDepth};
+
+/// A deterministic config: fixed color depth and no heartbeat.
+pub fn config() -> Config {
+    Config {
+        color_depth: ColorDepth::TrueColor,
+        pulse: 0.0,
+        ..Config::default()
+    }
+}
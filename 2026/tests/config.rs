@@ -0,0 +1,72 @@
+//! Config files: validation of each setting and picking up edits.
+
+use std::{
+    fs::{self, File},
+    thread,
+    time::{Duration, SystemTime},
+};
+
+use ratatui_heart::config::{Config, ConfigWatcher};
+
+#[test]
+fn out_of_range_values_are_rejected() {
+    for source in [
+        "fps = 0.1",
+        "fps = 500.0",
+        "layers = 0",
+        "layers = 100",
+        "thickness = -0.1",
+        "thickness = 0.9",
+        "bounds = 0.0",
+        "bounds = -2.0",
+        "bounds = nan",
+        "steps = 4",
+    ] {
+        let err = Config::from_toml(source).expect_err(source);
+        assert!(
+            err.to_string().contains(source.split(' ').next().unwrap()),
+            "`{source}` failed with `{err}`"
+        );
+    }
+    assert!(Config::from_toml("fps = 30.0\nlayers = 8\nthickness = 0.1\nbounds = 3.0").is_ok());
+}
+
+#[test]
+fn unknown_settings_are_rejected() {
+    assert!(Config::from_toml("colour = \"red\"").is_err());
+}
+
+#[test]
+fn edits_to_the_file_are_picked_up() {
+    let path = std::env::temp_dir().join(format!("ratatui_heart-{}.toml", std::process::id()));
+    fs::write(&path, "fps = 10.0").unwrap();
+    let mut watcher = ConfigWatcher::new(path.clone());
+    assert!(watcher.poll().is_none(), "nothing changed yet");
+
+    fs::write(&path, "fps = 20.0\nlayers = 3").unwrap();
+    // Make sure the edit is seen even where timestamps are coarse
+    let later = SystemTime::now() + Duration::from_secs(5);
+    File::options()
+        .write(true)
+        .open(&path)
+        .unwrap()
+        .set_modified(later)
+        .unwrap();
+    thread::sleep(Duration::from_millis(600));
+    let config = watcher.poll().expect("the edit was missed").unwrap();
+    assert_eq!((config.fps, config.layers), (20.0, 3));
+
+    // A broken edit is reported, and deleting the file means the defaults
+    fs::write(&path, "fps = \"fast\"").unwrap();
+    File::options()
+        .write(true)
+        .open(&path)
+        .unwrap()
+        .set_modified(later + Duration::from_secs(5))
+        .unwrap();
+    thread::sleep(Duration::from_millis(600));
+    assert!(watcher.poll().unwrap().is_err());
+    fs::remove_file(&path).unwrap();
+    thread::sleep(Duration::from_millis(600));
+    assert_eq!(watcher.poll().unwrap().unwrap(), Config::default());
+}
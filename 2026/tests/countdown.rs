@@ -0,0 +1,151 @@
+//! Counting down to Valentine's Day: dates, labels and the countdown scene.
+
+use std::time::Duration;
+
+use chrono::{NaiveDate, NaiveDateTime};
+use ratatui::{buffer::Buffer, layout::Rect, widgets::Widget};
+use ratatui_heart::{
+    config::Config,
+    countdown::{self, label, next_valentines, parse_target, remaining},
+    message::{Effect, Font, Position},
+    render::{canvas_area, cell_to_world},
+    scene::{Playlist, SceneKind},
+    widget::HeartWidget,
+};
+
+fn at(year: i32, month: u32, day: u32, hour: u32, minute: u32) -> NaiveDateTime {
+    NaiveDate::from_ymd_opt(year, month, day)
+        .unwrap()
+        .and_hms_opt(hour, minute, 0)
+        .unwrap()
+}
+
+#[test]
+fn the_next_valentines_day_is_this_year_until_it_has_passed() {
+    assert_eq!(
+        next_valentines(at(2026, 1, 3, 12, 0)),
+        at(2026, 2, 14, 0, 0)
+    );
+    // The whole day counts as arrived
+    assert_eq!(
+        next_valentines(at(2026, 2, 14, 23, 59)),
+        at(2026, 2, 14, 0, 0)
+    );
+    assert_eq!(
+        next_valentines(at(2026, 2, 15, 0, 0)),
+        at(2027, 2, 14, 0, 0)
+    );
+}
+
+#[test]
+fn remaining_time_stops_at_zero() {
+    let target = Some(at(2027, 2, 14, 0, 0));
+    assert_eq!(
+        remaining(target, at(2027, 2, 13, 23, 0)),
+        Duration::from_secs(3600)
+    );
+    assert_eq!(remaining(target, at(2027, 3, 1, 0, 0)), Duration::ZERO);
+    assert_eq!(remaining(None, at(2026, 2, 14, 9, 0)), Duration::ZERO);
+}
+
+#[test]
+fn labels_show_days_only_while_there_are_some() {
+    let time =
+        |d: u64, h: u64, m: u64, s: u64| Duration::from_secs(((d * 24 + h) * 60 + m) * 60 + s);
+    assert_eq!(label(time(3, 4, 5, 6)), "3d 04h 05m 06s");
+    assert_eq!(label(time(0, 23, 0, 59)), "23h 00m 59s");
+    assert_eq!(label(Duration::from_millis(999)), "00h 00m 00s");
+}
+
+#[test]
+fn targets_parse_with_or_without_a_time() {
+    assert_eq!(parse_target("2027-02-14").unwrap(), at(2027, 2, 14, 0, 0));
+    assert_eq!(
+        parse_target("2027-02-14 18:30").unwrap(),
+        at(2027, 2, 14, 18, 30)
+    );
+    assert_eq!(
+        parse_target("2027-02-14T18:30:00").unwrap(),
+        at(2027, 2, 14, 18, 30)
+    );
+    assert!(parse_target("14/02/2027").is_err());
+    assert!(parse_target("2027-02-30").is_err());
+}
+
+#[test]
+fn config_reads_targets_as_strings_or_toml_dates() {
+    let config = Config::from_toml("countdown-target = \"2027-02-14 18:30\"").unwrap();
+    assert_eq!(config.countdown_target, Some(at(2027, 2, 14, 18, 30)));
+    let config = Config::from_toml("countdown-target = 2027-02-14").unwrap();
+    assert_eq!(config.countdown_target, Some(at(2027, 2, 14, 0, 0)));
+    assert!(Config::from_toml("countdown-target = \"soon\"").is_err());
+}
+
+#[test]
+fn the_countdown_shows_the_time_left_beside_the_heart() {
+    let base = Config {
+        countdown_target: Some(at(2027, 2, 14, 0, 0)),
+        ..Config::default()
+    };
+    let config = SceneKind::Countdown.configure(&base, None, at(2027, 2, 12, 22, 0));
+    assert_eq!(config.message.as_deref(), Some("1d 02h 00m 00s"));
+    assert_eq!(config.message_position, Position::Beside);
+    assert!(!SceneKind::Countdown.celebrates(&base, at(2027, 2, 12, 22, 0)));
+}
+
+#[test]
+fn the_countdown_celebrates_at_zero() {
+    let base = Config {
+        countdown_target: Some(at(2027, 2, 14, 0, 0)),
+        ..Config::default()
+    };
+    let now = at(2027, 2, 14, 0, 0);
+    assert!(SceneKind::Countdown.celebrates(&base, now));
+    let config = SceneKind::Countdown.configure(&base, None, now);
+    assert_eq!(config.message.as_deref(), Some("Happy Valentine's Day!"));
+    assert_eq!(config.message_font, Font::Big);
+    assert_eq!(config.message_effect, Effect::Typewriter);
+    assert!(config.heart_rate >= 6.0);
+    // A scene's own message replaces the default greeting
+    let config = SceneKind::Countdown.configure(&base, Some("Finally"), now);
+    assert_eq!(config.message.as_deref(), Some("Finally"));
+}
+
+#[test]
+fn beside_puts_the_message_right_of_the_heart() {
+    let area = Rect::new(0, 0, 60, 12);
+    let mut buf = Buffer::empty(area);
+    HeartWidget::new()
+        .config(Config {
+            message: Some(countdown::label(Duration::from_secs(90_061))),
+            message_position: Position::Beside,
+            ..Config::default()
+        })
+        .render(area, &mut buf);
+    let row = (0..area.height)
+        .map(|y| {
+            (0..area.width)
+                .map(|x| buf[(x, y)].symbol())
+                .collect::<String>()
+        })
+        .find(|row| row.contains("1d 01h 01m 01s"))
+        .expect("the label is drawn");
+    assert!(row.find("1d").unwrap() >= usize::from(area.width / 2));
+}
+
+#[test]
+fn clicks_land_on_the_countdown_heart_not_the_whole_terminal() {
+    let config = Config {
+        countdown: true,
+        countdown_target: Some(at(2099, 2, 14, 0, 0)),
+        ..Config::default()
+    };
+    let active = Playlist::new(&config).config(&config, 0.0);
+    assert_eq!(active.message_position, Position::Beside);
+
+    // The label takes the right of the terminal, so a click there misses
+    let area = Rect::new(0, 0, 80, 24);
+    assert!(canvas_area(area, &active).width < canvas_area(area, &config).width);
+    assert!(cell_to_world(area, &config, 75, 12).is_some());
+    assert_eq!(cell_to_world(area, &active, 75, 12), None);
+}
@@ -0,0 +1,76 @@
+//! Checks of the exported animation files.
+
+use ratatui_heart::{config::Config, export};
+
+use common::config;
+
+mod common;
+
+#[test]
+fn gif_has_one_frame_per_tick() {
+    let config = Config {
+        fps: 20.0,
+        ..config()
+    };
+    let mut bytes = Vec::new();
+    export::gif::write_gif(&config, 0, 4, (20, 8), &mut bytes).unwrap();
+
+    let mut decoder = gif::DecodeOptions::new().read_info(&bytes[..]).unwrap();
+    assert_eq!((decoder.width(), decoder.height()), (160, 128));
+    let mut frames = 0;
+    while let Some(frame) = decoder.read_next_frame().unwrap() {
+        assert_eq!(frame.delay, 5);
+        frames += 1;
+    }
+    assert_eq!(frames, 4);
+}
+
+#[test]
+fn gif_rejects_oversized_frames() {
+    let mut bytes = Vec::new();
+    assert!(export::gif::write_gif(&config(), 0, 1, (10_000, 8), &mut bytes).is_err());
+}
+
+#[test]
+fn cast_has_a_header_and_one_event_per_tick() {
+    let config = Config {
+        fps: 10.0,
+        ..config()
+    };
+    let mut bytes = Vec::new();
+    export::cast::write_cast(&config, 0, 3, (20, 8), &mut bytes).unwrap();
+
+    let lines: Vec<serde_json::Value> = String::from_utf8(bytes)
+        .unwrap()
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect();
+    assert_eq!(lines[0]["version"], 2);
+    assert_eq!(
+        (lines[0]["width"].clone(), lines[0]["height"].clone()),
+        (20.into(), 8.into())
+    );
+    let times: Vec<f64> = lines[1..]
+        .iter()
+        .map(|event| event[0].as_f64().unwrap())
+        .collect();
+    assert_eq!(times, [0.0, 0.1, 0.2]);
+    assert!(lines[1..].iter().all(|event| event[1] == "o"));
+}
+
+#[test]
+fn svg_steps_through_every_frame() {
+    let config = Config {
+        message: Some(String::from("<3 & you")),
+        ..config()
+    };
+    let mut bytes = Vec::new();
+    export::svg::write_svg(&config, 0, 5, (20, 8), &mut bytes).unwrap();
+
+    let svg = String::from_utf8(bytes).unwrap();
+    assert!(svg.starts_with("<svg "));
+    assert!(svg.contains("steps(5)"));
+    assert_eq!(svg.matches("<g transform=").count(), 5);
+    assert!(svg.contains("&lt;3"));
+    assert!(svg.contains("&amp;"));
+}
@@ -0,0 +1,78 @@
+//! Color depth: detecting what the terminal shows and reducing colors to it.
+
+use ratatui::style::Color;
+use ratatui_heart::palette::ColorDepth;
+
+#[test]
+fn the_depth_is_read_from_colorterm_and_term() {
+    for (colorterm, term, depth) in [
+        ("truecolor", "xterm-256color", ColorDepth::TrueColor),
+        ("24bit", "xterm", ColorDepth::TrueColor),
+        ("TrueColor", "", ColorDepth::TrueColor),
+        ("", "xterm-direct", ColorDepth::TrueColor),
+        ("", "xterm-256color", ColorDepth::Ansi256),
+        ("", "screen-256color", ColorDepth::Ansi256),
+        ("yes", "xterm", ColorDepth::Ansi16),
+        ("", "linux", ColorDepth::Ansi16),
+        ("", "", ColorDepth::Ansi16),
+    ] {
+        assert_eq!(
+            ColorDepth::detect(colorterm, term),
+            depth,
+            "COLORTERM={colorterm:?} TERM={term:?}"
+        );
+    }
+}
+
+#[test]
+fn chosen_depths_are_kept() {
+    for depth in [
+        ColorDepth::TrueColor,
+        ColorDepth::Ansi256,
+        ColorDepth::Ansi16,
+    ] {
+        assert_eq!(depth.resolve(), depth);
+    }
+    assert_ne!(ColorDepth::Auto.resolve(), ColorDepth::Auto);
+}
+
+#[test]
+fn truecolor_and_auto_leave_colors_alone() {
+    for depth in [ColorDepth::TrueColor, ColorDepth::Auto] {
+        for color in [Color::Rgb(1, 2, 3), Color::Indexed(200), Color::Red] {
+            assert_eq!(depth.quantize(color), color);
+        }
+    }
+}
+
+#[test]
+fn the_256_color_palette_takes_the_nearest_cube_or_gray() {
+    let depth = ColorDepth::Ansi256;
+    assert_eq!(depth.quantize(Color::Rgb(255, 0, 0)), Color::Indexed(196));
+    assert_eq!(
+        depth.quantize(Color::Rgb(255, 100, 150)),
+        Color::Indexed(204)
+    );
+    assert_eq!(depth.quantize(Color::Rgb(0, 0, 0)), Color::Indexed(16));
+    // Grays go to the finer gray ramp
+    assert_eq!(
+        depth.quantize(Color::Rgb(128, 128, 128)),
+        Color::Indexed(244)
+    );
+    // Indexed and named colors already fit
+    assert_eq!(depth.quantize(Color::Indexed(100)), Color::Indexed(100));
+    assert_eq!(depth.quantize(Color::Magenta), Color::Magenta);
+}
+
+#[test]
+fn sixteen_colors_take_the_nearest_named_color() {
+    let depth = ColorDepth::Ansi16;
+    assert_eq!(depth.quantize(Color::Rgb(250, 5, 5)), Color::LightRed);
+    assert_eq!(depth.quantize(Color::Rgb(190, 10, 10)), Color::Red);
+    assert_eq!(depth.quantize(Color::Rgb(10, 10, 10)), Color::Black);
+    assert_eq!(depth.quantize(Color::Rgb(250, 250, 250)), Color::White);
+    assert_eq!(depth.quantize(Color::Indexed(196)), Color::LightRed);
+    // The basic colors are left as they are
+    assert_eq!(depth.quantize(Color::Indexed(5)), Color::Indexed(5));
+    assert_eq!(depth.quantize(Color::Blue), Color::Blue);
+}
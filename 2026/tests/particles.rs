@@ -0,0 +1,171 @@
+//! Spawning, aging and capping of the particle effects.
+
+use ratatui_heart::{
+    config::Config,
+    particles::{Kind, Particles},
+    view::View,
+};
+
+/// World bounds of a square canvas.
+fn square(config: &Config) -> ([f64; 2], [f64; 2]) {
+    let bounds = [-config.bounds, config.bounds];
+    (bounds, bounds)
+}
+
+/// Step `particles` through `seconds` of animation at 10 updates per second.
+fn run(particles: &mut Particles, config: &Config, seconds: f64) {
+    for step in 1..=(seconds * 10.0) as u32 {
+        particles.update(
+            config,
+            &View::default(),
+            square(config),
+            f64::from(step) / 10.0,
+            0.1,
+        );
+    }
+}
+
+#[test]
+fn hearts_rise_at_the_configured_rate() {
+    let config = Config {
+        heart_rate: 2.0,
+        sparkles: 0,
+        ..Config::default()
+    };
+    let mut particles = Particles::new(0);
+    run(&mut particles, &config, 3.0);
+
+    assert!((5..=6).contains(&particles.len()), "{}", particles.len());
+    assert!(particles.iter().all(|p| p.kind == Kind::Heart));
+    assert!(particles.iter().all(|p| p.position.1 >= -config.bounds));
+}
+
+#[test]
+fn sparkles_burst_on_each_beat_and_fade_out() {
+    let config = Config {
+        heart_rate: 0.0,
+        sparkles: 10,
+        bpm: 60.0,
+        ..Config::default()
+    };
+    let mut particles = Particles::new(0);
+    run(&mut particles, &config, 1.0);
+    assert_eq!(particles.len(), 10);
+    assert!(particles.iter().all(|p| p.kind == Kind::Sparkle));
+
+    // Sparkles live under a second and the next beat is a second away
+    particles.update(&config, &View::default(), square(&config), 1.95, 0.95);
+    assert!(particles.is_empty());
+}
+
+#[test]
+fn a_burst_starts_where_it_was_asked_to() {
+    let config = Config {
+        sparkles: 6,
+        ..Config::default()
+    };
+    let mut particles = Particles::new(0);
+    particles.burst(&config, (1.0, -0.5));
+
+    assert_eq!(particles.len(), 7);
+    assert_eq!(
+        particles.iter().filter(|p| p.kind == Kind::Heart).count(),
+        1
+    );
+    assert!(particles.iter().all(|p| p.position == (1.0, -0.5)));
+}
+
+#[test]
+fn sparkles_leave_the_outline_where_the_view_put_it() {
+    let config = Config {
+        heart_rate: 0.0,
+        bpm: 60.0,
+        pulse: 0.0,
+        ..Config::default()
+    };
+    let view = View {
+        offset: (10.0, 0.0),
+        ..View::default()
+    };
+    let mut particles = Particles::new(0);
+    let wide = ([-20.0, 20.0], [-2.0, 2.0]);
+    particles.update(&config, &view, wide, 0.5, 0.0);
+    particles.update(&config, &view, wide, 1.0, 0.0);
+
+    assert_eq!(particles.len(), 8);
+    assert!(particles.iter().all(|p| p.position.0 > 8.0));
+}
+
+#[test]
+fn particles_are_capped() {
+    let config = Config {
+        heart_rate: 50.0,
+        confetti: 1000,
+        max_particles: 120,
+        ..Config::default()
+    };
+    let mut particles = Particles::new(0);
+    particles.confetti(&config, square(&config));
+    run(&mut particles, &config, 2.0);
+    assert!(particles.len() <= 120);
+}
+
+#[test]
+fn same_seed_same_particles() {
+    let config = Config::default();
+    let (mut a, mut b) = (Particles::new(7), Particles::new(7));
+    for particles in [&mut a, &mut b] {
+        particles.confetti(&config, square(&config));
+        run(particles, &config, 2.0);
+    }
+    assert_eq!(a, b);
+}
+
+#[test]
+fn particles_live_on_across_a_wide_canvas() {
+    let config = Config {
+        heart_rate: 0.0,
+        sparkles: 8,
+        ..Config::default()
+    };
+    // An 80x24 terminal shows the world out to about x = 3.3
+    let wide = ([-3.3, 3.3], [-2.0, 2.0]);
+    let mut particles = Particles::new(0);
+    particles.burst(&config, (-3.0, 0.0));
+    particles.update(&config, &View::default(), wide, 0.1, 0.1);
+    assert_eq!(particles.len(), 9);
+
+    // Gone once well past the edge
+    particles.update(
+        &config,
+        &View::default(),
+        ([-1.0, 1.0], [-1.0, 1.0]),
+        0.2,
+        0.1,
+    );
+    assert!(particles.is_empty());
+}
+
+#[test]
+fn hearts_and_confetti_spread_across_a_wide_canvas() {
+    let config = Config {
+        heart_rate: 20.0,
+        sparkles: 0,
+        confetti: 50,
+        ..Config::default()
+    };
+    let wide = ([-4.0, 4.0], [-1.5, 1.5]);
+    let mut particles = Particles::new(0);
+    particles.update(&config, &View::default(), wide, 1.0, 1.0);
+    particles.confetti(&config, wide);
+
+    let (hearts, confetti): (Vec<_>, Vec<_>) =
+        particles.iter().partition(|p| p.kind == Kind::Heart);
+    assert!(hearts.iter().all(|p| p.position.1 == -1.5));
+    assert!(confetti.iter().all(|p| p.position.1 == 1.5));
+    // Both reach well past the square the heart sits in
+    for spawned in [&hearts, &confetti] {
+        assert!(spawned.iter().any(|p| p.position.0 < -config.bounds));
+        assert!(spawned.iter().any(|p| p.position.0 > config.bounds));
+    }
+}
@@ -0,0 +1,82 @@
+//! The heartbeat: its period, the lub and dub, and the easing curves.
+
+use ratatui_heart::pulse::{Easing, Heartbeat};
+
+const EASINGS: [Easing; 4] = [Easing::Linear, Easing::Sine, Easing::Cubic, Easing::Expo];
+
+fn heartbeat(bpm: f64, amplitude: f64) -> Heartbeat {
+    Heartbeat {
+        bpm,
+        amplitude,
+        easing: Easing::Sine,
+    }
+}
+
+fn assert_close(actual: f64, expected: f64) {
+    assert!((actual - expected).abs() < 1e-9, "{actual} != {expected}");
+}
+
+#[test]
+fn beats_repeat_every_sixty_seconds_over_bpm() {
+    let beat = heartbeat(72.0, 0.1);
+    let period = 60.0 / 72.0;
+    for i in 0..20 {
+        let t = f64::from(i) * 0.037;
+        assert_close(beat.scale(t + period), beat.scale(t));
+        assert_close(beat.scale(t + 3.0 * period), beat.scale(t));
+    }
+    // Twice the rate, half the period
+    let fast = heartbeat(144.0, 0.1);
+    assert_close(fast.scale(0.2), beat.scale(0.4));
+}
+
+#[test]
+fn a_strong_lub_is_followed_by_a_softer_dub_and_a_rest() {
+    // One beat per second, so times are fractions of the beat
+    let beat = heartbeat(60.0, 0.1);
+    assert_close(beat.scale(0.0), 1.0);
+    // The lub peaks a third of the way into its quarter beat
+    assert_close(beat.scale(0.25 * 0.35), 1.1);
+    // The dub peaks lower
+    assert_close(beat.scale(0.3 + 0.3 * 0.35), 1.06);
+    assert_close(beat.scale(0.8), 1.0);
+}
+
+#[test]
+fn no_pulse_keeps_the_heart_still() {
+    for beat in [heartbeat(72.0, 0.0), heartbeat(0.0, 0.1)] {
+        for i in 0..50 {
+            assert_eq!(beat.scale(f64::from(i) * 0.05), 1.0);
+        }
+    }
+}
+
+#[test]
+fn easings_rise_from_zero_to_one_through_the_middle() {
+    for easing in EASINGS {
+        assert_close(easing.ease(0.0), 0.0);
+        assert_close(easing.ease(0.5), 0.5);
+        assert_close(easing.ease(1.0), 1.0);
+        // Symmetric about the middle and never falling back
+        for i in 0..=20 {
+            let x = f64::from(i) / 20.0;
+            assert_close(easing.ease(1.0 - x), 1.0 - easing.ease(x));
+            assert!(
+                easing.ease(x + 0.05) >= easing.ease(x),
+                "{easing:?} falls at {x}"
+            );
+        }
+        // Out of range input is clamped
+        assert_eq!(easing.ease(-1.0), 0.0);
+        assert_eq!(easing.ease(2.0), 1.0);
+    }
+}
+
+#[test]
+fn sharper_easings_start_slower() {
+    let quarter = EASINGS.map(|easing| easing.ease(0.25));
+    assert_close(quarter[0], 0.25);
+    assert_close(quarter[1], 0.5 - 0.5 * std::f64::consts::FRAC_1_SQRT_2);
+    assert_close(quarter[2], 0.0625);
+    assert_close(quarter[3], 1.0 / 64.0);
+}
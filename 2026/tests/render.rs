@@ -0,0 +1,114 @@
+//! Geometry caching, world bounds and the view transform of the renderer.
+
+use ratatui::layout::Rect;
+use ratatui_heart::{
+    config::Config,
+    render::{self, Geometry},
+    shape::{Point, ShapeKind},
+    view::{View, ZOOM_RANGE},
+};
+
+#[test]
+fn geometry_is_rebuilt_only_when_the_shape_or_resolution_changes() {
+    let mut config = Config::default();
+    let mut geometry = Geometry::default();
+    assert!(geometry.update(&config, 24.0));
+    let outlines = geometry.outlines().to_vec();
+
+    // Settings that do not affect the outlines keep the cache
+    config.fps = 30.0;
+    config.filled = true;
+    config.palette = String::from("rose");
+    assert!(!geometry.update(&config, 24.0));
+    assert_eq!(geometry.outlines(), outlines);
+
+    // A resize changes the resolution
+    assert!(geometry.update(&config, 48.0));
+    assert_ne!(geometry.outlines(), outlines);
+
+    config.shape = ShapeKind::Star;
+    assert!(geometry.update(&config, 48.0));
+    assert!(!geometry.update(&config, 48.0));
+}
+
+#[test]
+fn steps_follow_the_resolution_unless_fixed() {
+    let mut config = Config::default();
+    let mut geometry = Geometry::default();
+
+    geometry.update(&config, 10.0);
+    let coarse = geometry.steps();
+    geometry.update(&config, 40.0);
+    let fine = geometry.steps();
+    assert!(fine > 3 * coarse, "{coarse} -> {fine}");
+
+    config.steps = Some(500);
+    geometry.update(&config, 10.0);
+    assert_eq!(geometry.steps(), 500);
+}
+
+#[test]
+fn world_bounds_keep_cells_in_proportion() {
+    let config = Config::default();
+
+    // 80 columns are as wide as 40 rows of cells twice as tall
+    let (x, y) = render::world_bounds(Rect::new(0, 0, 80, 24), &config);
+    assert_eq!(y, [-2.0, 2.0]);
+    assert!((x[1] - 2.0 * 80.0 / 48.0).abs() < 1e-12);
+    assert_eq!(x[0], -x[1]);
+
+    // A narrow area shows more of the world vertically instead
+    let (x, y) = render::world_bounds(Rect::new(0, 0, 20, 24), &config);
+    assert_eq!(x, [-2.0, 2.0]);
+    assert!((y[1] - 2.0 * 48.0 / 20.0).abs() < 1e-12);
+
+    // Square cells give square bounds on a square area
+    let config = Config {
+        cell_aspect: 1.0,
+        ..config
+    };
+    let (x, y) = render::world_bounds(Rect::new(5, 5, 30, 30), &config);
+    assert_eq!((x, y), ([-2.0, 2.0], [-2.0, 2.0]));
+}
+
+#[test]
+fn cells_map_to_the_world_through_the_canvas_bounds() {
+    let config = Config {
+        message: Some(String::from("hi")),
+        ..Config::default()
+    };
+    let area = Rect::new(0, 0, 40, 21);
+    // The message takes the bottom row, leaving a 40x20 canvas
+    assert_eq!(render::canvas_area(area, &config), Rect::new(0, 0, 40, 20));
+
+    let (x, y) = render::cell_to_world(area, &config, 0, 0).unwrap();
+    assert!(
+        (x - -1.95).abs() < 1e-12 && (y - 1.9).abs() < 1e-12,
+        "{x} {y}"
+    );
+    let (x, y) = render::cell_to_world(area, &config, 39, 19).unwrap();
+    assert!(
+        (x - 1.95).abs() < 1e-12 && (y - -1.9).abs() < 1e-12,
+        "{x} {y}"
+    );
+    assert_eq!(render::cell_to_world(area, &config, 10, 20), None);
+}
+
+#[test]
+fn the_view_moves_turns_and_zooms_the_heart() {
+    let close = |a: Point, b: Point| (a.0 - b.0).abs() < 1e-12 && (a.1 - b.1).abs() < 1e-12;
+    let mut view = View::default();
+    assert_eq!(view.apply((1.0, 2.0)), (1.0, 2.0));
+
+    view.translate((0.0, 0.0), (1.0, -1.0));
+    assert!(close(view.apply((0.0, 0.0)), (1.0, -1.0)));
+
+    // A quarter turn around the moved centre
+    view.rotate((2.0, -1.0), (1.0, 0.0));
+    assert!(close(view.apply((1.0, 0.0)), (1.0, 0.0)));
+
+    view.zoom_by(2.0);
+    assert!(close(view.apply((1.0, 0.0)), (1.0, 1.0)));
+    view.zoom_by(100.0);
+    assert_eq!(view.zoom, *ZOOM_RANGE.end());
+}
@@ -0,0 +1,177 @@
+//! Scene playlists: timing, skipping, transitions and config.
+
+use std::time::Duration;
+
+use ratatui::{buffer::Buffer, layout::Rect, style::Color};
+use ratatui_heart::{
+    config::Config,
+    headless::{self, Format},
+    palette::ColorDepth,
+    particles::Kind,
+    scene::{self, Playlist, SceneConfig, SceneKind, Transition},
+    view::View,
+};
+
+fn scenes(kinds: &[(SceneKind, u64)]) -> Config {
+    Config {
+        color_depth: ColorDepth::TrueColor,
+        scenes: kinds
+            .iter()
+            .map(|&(kind, seconds)| SceneConfig {
+                kind,
+                duration: Some(Duration::from_secs(seconds)),
+                ..SceneConfig::default()
+            })
+            .collect(),
+        ..Config::default()
+    }
+}
+
+#[test]
+fn a_lone_heart_runs_on_the_animation_clock() {
+    let playlist = Playlist::new(&Config::default());
+    assert_eq!(playlist.len(), 1);
+    assert_eq!(playlist.position(123.5), (0, 123.5));
+}
+
+#[test]
+fn scenes_take_turns_and_loop() {
+    let config = scenes(&[(SceneKind::Heart, 2), (SceneKind::Solid, 3)]);
+    let playlist = Playlist::new(&config);
+    assert_eq!(playlist.position(0.5), (0, 0.5));
+    assert_eq!(playlist.position(2.5), (1, 0.5));
+    assert_eq!(playlist.position(5.5), (0, 0.5));
+    // Playing backwards past the start wraps to the end
+    assert_eq!(playlist.position(-0.5), (1, 2.5));
+}
+
+#[test]
+fn next_and_previous_skip_to_scene_starts() {
+    let config = scenes(&[
+        (SceneKind::Heart, 2),
+        (SceneKind::Message, 3),
+        (SceneKind::Shower, 4),
+    ]);
+    let mut playlist = Playlist::new(&config);
+    playlist.next(1.0);
+    assert_eq!(playlist.position(1.0), (1, 0.0));
+    playlist.next(1.5);
+    assert_eq!(playlist.position(1.5), (2, 0.0));
+    playlist.previous(2.0);
+    assert_eq!(playlist.position(2.0), (1, 0.0));
+    playlist.previous(2.0);
+    playlist.previous(2.0);
+    assert_eq!(playlist.position(2.0), (2, 0.0));
+}
+
+#[test]
+fn the_message_scene_types_out_big_letters() {
+    let config = Config {
+        message: Some(String::from("Hi")),
+        transition: Transition::Cut,
+        ..scenes(&[(SceneKind::Heart, 2), (SceneKind::Message, 2)])
+    };
+    let text = |tick| Format::Text.encode(&headless::render_frame(&config, tick, 40, 16));
+    assert!(!text(15).contains('█'));
+    assert!(text(35).contains('█'));
+}
+
+#[test]
+fn wipes_uncover_the_new_scene_from_the_left() {
+    let area = Rect::new(0, 0, 4, 1);
+    let from = Buffer::with_lines(["aaaa"]);
+    let mut to = Buffer::with_lines(["bbbb"]);
+    scene::blend(&from, &mut to, Transition::Wipe, 0.5, &Config::default());
+    assert_eq!(to, Buffer::with_lines(["bbaa"]));
+    assert_eq!(to.area, area);
+}
+
+#[test]
+fn crossfades_blend_colors_and_keep_the_stronger_symbol() {
+    let config = Config {
+        color_depth: ColorDepth::TrueColor,
+        ..Config::default()
+    };
+    let mut from = Buffer::with_lines(["ab "]);
+    let mut to = Buffer::with_lines(["c  "]);
+    for cell in from.content.iter_mut().chain(to.content.iter_mut()) {
+        cell.set_fg(Color::Rgb(200, 0, 0));
+    }
+    scene::blend(&from, &mut to, Transition::Crossfade, 0.25, &config);
+
+    assert_eq!(to.content[0].symbol(), "a");
+    assert_eq!(to.content[0].fg, Color::Rgb(200, 0, 0));
+    // Only the old frame has ink here, so it fades out
+    assert_eq!(to.content[1].symbol(), "b");
+    assert_eq!(to.content[1].fg, Color::Rgb(150, 0, 0));
+    assert_eq!(to.content[2].symbol(), " ");
+}
+
+#[test]
+fn scenes_are_read_from_the_config_file() {
+    let config = Config::from_toml(
+        r#"
+        scene-duration = "20s"
+        transition = "wipe"
+
+        [[scenes]]
+        kind = "heart"
+
+        [[scenes]]
+        kind = "message"
+        duration = "5s"
+        transition = "cut"
+        message = "Be mine"
+        "#,
+    )
+    .unwrap();
+    assert_eq!(config.scene_duration, Duration::from_secs(20));
+    assert_eq!(config.transition, Transition::Wipe);
+    assert_eq!(config.scenes.len(), 2);
+    assert_eq!(config.scenes[1].duration, Some(Duration::from_secs(5)));
+    assert_eq!(config.scenes[1].message.as_deref(), Some("Be mine"));
+    assert_eq!(Playlist::new(&config).position(22.0), (1, 2.0));
+
+    assert!(Config::from_toml("[[scenes]]\nduration = \"0s\"").is_err());
+}
+
+#[test]
+fn the_old_scene_keeps_moving_while_it_fades_out() {
+    let config = scenes(&[(SceneKind::Shower, 2), (SceneKind::Heart, 2)]);
+    let mut playlist = Playlist::new(&config);
+    let view = View::default();
+    for tick in 1..=20 {
+        playlist.update(&config, &view, f64::from(tick) * 0.1, 0.1);
+    }
+    // Halfway through the crossfade into the heart scene
+    let before = playlist.particles(1.9).unwrap().clone();
+    playlist.update(&config, &view, 2.5, 0.5);
+    assert_ne!(playlist.particles(1.9).unwrap(), &before);
+}
+
+#[test]
+fn playlists_are_rebuilt_when_the_config_changes() {
+    let mut config = scenes(&[(SceneKind::Heart, 2), (SceneKind::Message, 2)]);
+    let mut playlist = Playlist::new(&config);
+    config.scenes.truncate(1);
+    playlist.update(&config, &View::default(), 0.5, 0.1);
+    assert_eq!(playlist.len(), 1);
+}
+
+#[test]
+fn a_lone_scene_starts_afresh_each_loop() {
+    let config = scenes(&[(SceneKind::Shower, 2)]);
+    let mut playlist = Playlist::new(&config);
+    let view = View::default();
+    for tick in 1..=39 {
+        playlist.update(&config, &view, f64::from(tick) * 0.1, 0.1);
+    }
+    // The second loop threw its own confetti, 1.5 seconds in
+    let particles = playlist.particles(3.9).unwrap();
+    let confetti: Vec<_> = particles
+        .iter()
+        .filter(|p| p.kind == Kind::Confetti)
+        .collect();
+    assert!(!confetti.is_empty());
+    assert!(confetti.iter().all(|p| p.age < 1.0));
+}
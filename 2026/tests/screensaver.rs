@@ -0,0 +1,102 @@
+//! The screensaver: bouncing, hue shifts and burn-in protection.
+
+use ratatui::{buffer::Buffer, layout::Rect, style::Color};
+use ratatui_heart::{
+    palette::rotate_hue,
+    render::{canvas_area, world_bounds},
+    screensaver::Screensaver,
+};
+
+use common::config;
+
+mod common;
+
+#[test]
+fn the_heart_bounces_around_inside_the_canvas() {
+    let config = config();
+    let area = Rect::new(0, 0, 60, 20);
+    let (x_bounds, y_bounds) = world_bounds(canvas_area(area, &config), &config);
+    let mut saver = Screensaver::new(0);
+    let mut xs = Vec::new();
+    for _ in 0..2000 {
+        saver.update(area, &config, 0.1);
+        let (x, y) = saver.view().offset;
+        assert!(x_bounds[0] < x && x < x_bounds[1], "{x} left the canvas");
+        assert!(y_bounds[0] < y && y < y_bounds[1], "{y} left the canvas");
+        xs.push(x);
+    }
+    // It turns back at both edges instead of sticking to one
+    let turns = xs
+        .windows(3)
+        .filter(|w| (w[1] - w[0]) * (w[2] - w[1]) < 0.0)
+        .count();
+    assert!(turns >= 4, "only {turns} turns");
+}
+
+#[test]
+fn a_heart_left_outside_by_a_resize_comes_back() {
+    let config = config();
+    let small = Rect::new(0, 0, 24, 12);
+    let (x_bounds, _) = world_bounds(canvas_area(small, &config), &config);
+    let mut saver = Screensaver::new(0);
+    // Wander a wide screen until the heart is right of where a small one ends
+    let outside = (0..1000).any(|_| {
+        saver.update(Rect::new(0, 0, 200, 50), &config, 0.1);
+        saver.view().offset.0 > x_bounds[1]
+    });
+    assert!(outside);
+    saver.update(small, &config, 0.1);
+    let (x, _) = saver.view().offset;
+    assert!(x_bounds[0] < x && x < x_bounds[1], "{x} is still outside");
+}
+
+#[test]
+fn colors_slowly_turn_round_the_hue_wheel() {
+    let config = config();
+    let area = Rect::new(0, 0, 40, 12);
+    let mut saver = Screensaver::new(0);
+    assert_eq!(saver.hue(), 0.0);
+    saver.update(area, &config, 10.0);
+    let hue = saver.hue();
+    assert!(hue > 0.0 && hue < 1.0, "{hue}");
+
+    assert_eq!(
+        rotate_hue(Color::Rgb(255, 0, 0), std::f64::consts::TAU / 3.0),
+        Color::Rgb(0, 255, 0)
+    );
+}
+
+#[test]
+fn the_picture_moves_by_one_cell_now_and_then() {
+    let config = config();
+    let area = Rect::new(0, 0, 4, 3);
+    let mut saver = Screensaver::new(0);
+    assert_eq!(saver.shift(), (0, 0));
+    saver.update(area, &config, 61.0);
+    assert_eq!(saver.shift(), (1, 0));
+    saver.update(area, &config, 60.0);
+    assert_eq!(saver.shift(), (1, 1));
+
+    let mut buf = Buffer::with_lines(["ab  ", "cd  ", "    "]);
+    saver.finish(&mut buf, &config);
+    assert_eq!(buf[(1, 1)].symbol(), "a");
+    assert_eq!(buf[(2, 2)].symbol(), "d");
+    assert_eq!(buf[(0, 0)].symbol(), " ");
+}
+
+#[test]
+fn the_heart_is_measured_again_when_the_config_changes() {
+    let mut config = config();
+    let area = Rect::new(0, 0, 24, 12);
+    let (x_bounds, _) = world_bounds(canvas_area(area, &config), &config);
+    let mut saver = Screensaver::new(0);
+    saver.update(area, &config, 0.1);
+    // A thicker heart has less room, so it is pulled in from where it was
+    config.layers = 8;
+    config.thickness = 0.5;
+    for _ in 0..200 {
+        saver.update(area, &config, 0.1);
+        let (x, _) = saver.view().offset;
+        assert!(x.abs() < 0.5 * x_bounds[1], "{x} is too close to the edge");
+    }
+}
@@ -0,0 +1,160 @@
+//! Snapshot tests of headless frames for every shape and palette.
+//!
+//! Review changes with `cargo insta review` after an intentional rendering change.
+
+use clap::ValueEnum;
+use ratatui_heart::{
+    config::Config,
+    headless::{self, Format},
+    message::{Effect, Font, Position},
+    palette::Palette,
+    shape::ShapeKind,
+};
+
+mod common;
+
+/// The shared test config with points for the polyline shape.
+fn config() -> Config {
+    Config {
+        points: vec![(0.0, 1.5), (1.5, -1.5), (-1.5, -1.5)],
+        ..common::config()
+    }
+}
+
+#[test]
+fn shapes() {
+    for &shape in ShapeKind::value_variants() {
+        let config = Config { shape, ..config() };
+        let frame = headless::render_frame(&config, 0, 40, 16);
+        let name = shape.to_possible_value().unwrap();
+        insta::assert_snapshot!(
+            format!("shape_{}", name.get_name()),
+            Format::Text.encode(&frame)
+        );
+    }
+}
+
+#[test]
+fn filled_shapes() {
+    for &shape in ShapeKind::value_variants() {
+        let config = Config {
+            shape,
+            filled: true,
+            ..config()
+        };
+        let frame = headless::render_frame(&config, 0, 40, 16);
+        let name = shape.to_possible_value().unwrap();
+        insta::assert_snapshot!(
+            format!("filled_{}", name.get_name()),
+            Format::Text.encode(&frame)
+        );
+    }
+}
+
+#[test]
+fn palettes() {
+    for &palette in Palette::value_variants() {
+        let name = palette.to_possible_value().unwrap();
+        let config = Config {
+            palette: name.get_name().to_owned(),
+            spread: 1.0,
+            ..config()
+        };
+        let frame = headless::render_frame(&config, 3, 24, 10);
+        insta::assert_snapshot!(
+            format!("palette_{}", name.get_name()),
+            Format::Ansi.encode(&frame)
+        );
+    }
+}
+
+#[test]
+fn frames_are_deterministic() {
+    let config = Config {
+        pulse: 0.08,
+        ..config()
+    };
+    for tick in [0, 5, 17] {
+        assert_eq!(
+            headless::render_frame(&config, tick, 30, 12),
+            headless::render_frame(&config, tick, 30, 12),
+        );
+    }
+}
+
+#[test]
+fn frame_runs_start_at_any_seed() {
+    let config = config();
+    let frames: Vec<_> = headless::render_frames(&config, u64::MAX, 3, 30, 12).collect();
+    assert_eq!(frames.len(), 3);
+    assert_eq!(frames[0], headless::render_frame(&config, u64::MAX, 30, 12));
+}
+
+#[test]
+fn message_is_centred_on_the_last_row() {
+    let config = Config {
+        message: Some(String::from("Be mine")),
+        ..config()
+    };
+    let text = Format::Text.encode(&headless::render_frame(&config, 0, 21, 8));
+    assert_eq!(text.lines().last(), Some("       Be mine"));
+}
+
+#[test]
+fn big_message_inside_the_heart() {
+    let config = Config {
+        message: Some(String::from("Hi")),
+        message_font: Font::Big,
+        message_position: Position::Inside,
+        ..config()
+    };
+    let frame = headless::render_frame(&config, 0, 40, 16);
+    insta::assert_snapshot!(Format::Text.encode(&frame));
+}
+
+#[test]
+fn big_message_falls_back_to_plain_when_too_wide() {
+    let config = Config {
+        message: Some(String::from("Be mine")),
+        message_font: Font::Big,
+        ..config()
+    };
+    let text = Format::Text.encode(&headless::render_frame(&config, 0, 21, 8));
+    assert_eq!(text.lines().last(), Some("       Be mine"));
+}
+
+#[test]
+fn message_effects_follow_time() {
+    let last_line = |effect, tick| {
+        let config = Config {
+            fps: 10.0,
+            message: Some(String::from("Be mine")),
+            message_effect: effect,
+            message_speed: 10.0,
+            ..config()
+        };
+        let text = Format::Text.encode(&headless::render_frame(&config, tick, 21, 8));
+        text.lines().last().unwrap().to_owned()
+    };
+
+    assert_eq!(last_line(Effect::Typewriter, 0), "");
+    assert_eq!(last_line(Effect::Typewriter, 4), "       Be m");
+    assert_eq!(last_line(Effect::Typewriter, 30), "       Be mine");
+    assert_eq!(last_line(Effect::Marquee, 0), "");
+    assert_eq!(last_line(Effect::Marquee, 3), "                  Be");
+    assert_eq!(last_line(Effect::Marquee, 21), "Be mine");
+    assert_eq!(last_line(Effect::Marquee, 24), "mine");
+}
+
+#[test]
+fn solid_heart_turned() {
+    let config = Config {
+        solid: true,
+        fps: 10.0,
+        spin: 0.25,
+        ..config()
+    };
+    // Half a second in, an eighth of a turn
+    let frame = headless::render_frame(&config, 5, 40, 16);
+    insta::assert_snapshot!(Format::Text.encode(&frame));
+}
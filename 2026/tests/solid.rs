@@ -0,0 +1,52 @@
+//! Mesh and software rendering of the solid heart.
+
+use std::f64::consts::TAU;
+
+use ratatui_heart::{
+    solid::{Mesh, Pose},
+    view::View,
+};
+
+fn pose(turns: f64) -> Pose {
+    Pose {
+        spin: turns * TAU,
+        scale: 1.0,
+        view: View::default(),
+    }
+}
+
+#[test]
+fn the_mesh_fits_the_default_world() {
+    let mesh = Mesh::heart(24, 48);
+    assert_eq!(mesh.len(), 2 * 24 * 48);
+    assert!(
+        mesh.positions()
+            .iter()
+            .all(|p| p.iter().all(|c| c.abs() < 2.0))
+    );
+}
+
+#[test]
+fn only_the_front_of_the_heart_is_visible() {
+    let mesh = Mesh::heart(24, 48);
+    for turns in [0.0, 0.1, 0.25, 0.4] {
+        let dots = mesh.render(&pose(turns), [-2.0, 2.0], [-2.0, 2.0], 80, 80);
+        assert!(!dots.is_empty());
+        // Without the depth buffer the back half would show through
+        assert!(dots.iter().all(|dot| dot.depth > 0.4), "turns {turns}");
+        assert!(dots.iter().all(|dot| (0.0..=1.0).contains(&dot.light)));
+    }
+}
+
+#[test]
+fn the_heart_is_thinner_from_the_side() {
+    let mesh = Mesh::heart(24, 48);
+    let front = mesh.render(&pose(0.0), [-2.0, 2.0], [-2.0, 2.0], 80, 80);
+    let side = mesh.render(&pose(0.25), [-2.0, 2.0], [-2.0, 2.0], 80, 80);
+    assert!(
+        side.len() < front.len() * 3 / 4,
+        "{} {}",
+        side.len(),
+        front.len()
+    );
+}
@@ -0,0 +1,196 @@
+//! Synchronized hearts: drift correction, leading and following, and tiles.
+
+use std::{
+    thread,
+    time::{Duration, Instant},
+};
+
+use ratatui::layout::Rect;
+use ratatui_heart::{
+    clock::Clock,
+    config::Config,
+    render::{canvas_area, world_bounds},
+    scene::{Playlist, SceneConfig, SceneKind},
+    sync::{self, Beat, Follower, Leader, SNAP, Tile, correct},
+    view::View,
+};
+
+fn assert_close(actual: f64, expected: f64) {
+    assert!((actual - expected).abs() < 1e-9, "{actual} != {expected}");
+}
+
+#[test]
+fn small_drift_is_made_up_gradually_and_large_gaps_at_once() {
+    // A quarter of a second at four times the drift per second closes it
+    assert_close(correct(10.0, 10.2, 0.25), 10.2);
+    // A shorter frame gets part of the way
+    assert_close(correct(10.0, 10.2, 0.05), 10.04);
+    assert_close(correct(10.0, 9.8, 0.05), 9.96);
+    // Far behind or ahead, the follower jumps
+    assert_close(correct(10.0, 10.0 + SNAP + 0.1, 0.01), 10.6);
+    assert_close(correct(10.0, 3.0, 0.01), 3.0);
+}
+
+#[test]
+fn beats_carry_the_clock_scene_and_view() {
+    let config = Config {
+        fps: 30.0,
+        cycle: 4.0,
+        ..Config::default()
+    };
+    let mut clock = Clock::new(3.0);
+    clock.reverse();
+    let mut playlist = Playlist::new(&config);
+    playlist.set_offset(-2.0);
+    let view = View {
+        zoom: 2.0,
+        ..View::default()
+    };
+    let beat = Beat::new(&clock, &config, &playlist, &view);
+    assert_close(beat.seconds, 3.0);
+    assert_close(beat.rate, -1.0);
+    assert_eq!(beat.tick, 90);
+    assert_close(beat.palette, 0.75);
+    assert_close(beat.scenes, -2.0);
+    assert_eq!(beat.view, view);
+}
+
+#[test]
+fn followers_lock_to_the_leader() {
+    let mut leader = Leader::bind("127.0.0.1:0".parse().unwrap()).unwrap();
+    let mut follower = Follower::connect(leader.address()).unwrap();
+    let config = Config {
+        scenes: vec![SceneConfig {
+            kind: SceneKind::Solid,
+            ..SceneConfig::default()
+        }],
+        ..Config::default()
+    };
+    let mut theirs = Clock::new(42.0);
+    theirs.faster();
+    let mut scenes = Playlist::new(&config);
+    scenes.next(42.0);
+    let view = View {
+        rotation: 1.0,
+        ..View::default()
+    };
+    let mut ours = Clock::new(0.0);
+
+    let deadline = Instant::now() + Duration::from_secs(2);
+    while !follower.is_locked(Instant::now()) {
+        assert!(Instant::now() < deadline, "no beat arrived");
+        let now = Instant::now();
+        follower.sync(&mut ours, now);
+        theirs.update_at(now);
+        leader.broadcast(&Beat::new(&theirs, &config, &scenes, &view), now);
+        thread::sleep(Duration::from_millis(10));
+    }
+    assert_eq!(leader.followers(), 1);
+
+    // The follower jumped to the leader's time and took on its speed
+    let now = Instant::now();
+    theirs.update_at(now);
+    ours.update_at(now);
+    follower.sync(&mut ours, now);
+    assert!((ours.seconds() - theirs.seconds()).abs() < 0.1);
+    assert_close(ours.rate(), theirs.rate());
+    // and has the leader's scene and view to show
+    let beat = follower.beat(now).unwrap();
+    assert_close(beat.scenes, scenes.offset());
+    assert_eq!(beat.view, view);
+}
+
+#[test]
+fn without_a_leader_the_clock_runs_free() {
+    // Nobody leads on this port: bind it, note it and let it go
+    let address = Leader::bind("127.0.0.1:0".parse().unwrap())
+        .unwrap()
+        .address();
+    let mut follower = Follower::connect(address).unwrap();
+    let mut clock = Clock::new(5.0);
+    assert_eq!(follower.sync(&mut clock, Instant::now()), 0.0);
+    assert!(!follower.is_locked(Instant::now()));
+    assert_close(clock.seconds(), 5.0);
+}
+
+#[test]
+fn sync_addresses_are_loopback_udp_ports() {
+    assert_eq!(
+        sync::parse_address("7878").unwrap(),
+        "127.0.0.1:7878".parse().unwrap()
+    );
+    assert!(sync::parse_address("0.0.0.0:7878").is_err());
+    assert!(sync::parse_address("/tmp/heart.sock").is_err());
+
+    let config = Config::from_toml("sync = \"follow\"\nsync-address = \"localhost:9000\"").unwrap();
+    assert_eq!(config.sync, Some(sync::Role::Follow));
+    assert_eq!(config.sync_address, "127.0.0.1:9000".parse().unwrap());
+}
+
+#[test]
+fn tiles_parse_as_a_grid_and_a_place_in_it() {
+    assert_eq!(
+        "3x2@2,1".parse::<Tile>().unwrap(),
+        Tile {
+            columns: 3,
+            rows: 2,
+            column: 2,
+            row: 1,
+        }
+    );
+    for bad in ["2x1", "2x1@2,0", "0x1@0,0", "9x1@0,0", "twoxone@0,0"] {
+        assert!(bad.parse::<Tile>().is_err(), "{bad} was accepted");
+    }
+    let config = Config::from_toml("tile = \"2x1@1,0\"").unwrap();
+    assert_eq!(config.tile.map(|tile| tile.column), Some(1));
+}
+
+#[test]
+fn tiles_show_their_part_of_one_heart() {
+    let config = Config::default();
+    let area = Rect::new(0, 0, 40, 20);
+    let ([_, x], [_, y]) = world_bounds(canvas_area(area, &config), &config);
+    let view = View::default();
+
+    // A single tile is the whole heart
+    let whole: Tile = "1x1@0,0".parse().unwrap();
+    assert_eq!(whole.place(&view, area, &config), view);
+
+    // Side by side, the heart's centre sits on the shared edge
+    let left = "2x1@0,0"
+        .parse::<Tile>()
+        .unwrap()
+        .place(&view, area, &config);
+    let right = "2x1@1,0"
+        .parse::<Tile>()
+        .unwrap()
+        .place(&view, area, &config);
+    assert_close(left.offset.0, x);
+    assert_close(right.offset.0, -x);
+    assert_close(left.zoom, 1.0);
+
+    // A 2x2 wall doubles the heart, centred on the shared corner
+    let corner = "2x2@1,1"
+        .parse::<Tile>()
+        .unwrap()
+        .place(&view, area, &config);
+    assert_close(corner.zoom, 2.0);
+    assert_close(corner.offset.0, -x);
+    assert_close(corner.offset.1, y);
+}
+
+#[test]
+fn points_on_a_tile_map_back_onto_the_whole_grid() {
+    let config = Config::default();
+    let area = Rect::new(0, 0, 40, 20);
+    let tile: Tile = "2x2@1,0".parse().unwrap();
+    let view = View {
+        offset: (0.3, -0.2),
+        ..View::default()
+    };
+    // The heart's origin as this tile draws it is the view's offset on the grid
+    let placed = tile.place(&view, area, &config);
+    let (x, y) = tile.to_grid(placed.offset, area, &config);
+    assert_close(x, 0.3);
+    assert_close(y, -0.2);
+}
@@ -0,0 +1,82 @@
+//! Embedding the heart in other layouts with `HeartWidget`.
+
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::Color,
+    widgets::{StatefulWidget, Widget},
+};
+use ratatui_heart::{
+    config::Config,
+    headless,
+    palette::ColorDepth,
+    shape::ShapeKind,
+    widget::{HeartState, HeartWidget},
+};
+
+#[test]
+fn the_widget_draws_what_the_app_draws() {
+    let config = Config {
+        fps: 10.0,
+        color_depth: ColorDepth::TrueColor,
+        message: Some(String::from("Be mine")),
+        ..Config::default()
+    };
+    let area = Rect::new(0, 0, 40, 16);
+    let mut buffer = Buffer::empty(area);
+    let heart = HeartWidget::new().config(config.clone()).seconds(0.4);
+    Widget::render(heart, area, &mut buffer);
+
+    assert_eq!(buffer, headless::render_frame(&config, 4, 40, 16));
+}
+
+#[test]
+fn the_widget_stays_inside_its_area() {
+    let mut buffer = Buffer::empty(Rect::new(0, 0, 60, 20));
+    let area = Rect::new(10, 2, 30, 12);
+    let heart = HeartWidget::new()
+        .shape(ShapeKind::Star)
+        .filled(true)
+        .thickness(0.1);
+    Widget::render(heart, area, &mut buffer);
+
+    for (index, cell) in buffer.content.iter().enumerate() {
+        let (x, y) = ((index % 60) as u16, (index / 60) as u16);
+        if !area.contains((x, y).into()) {
+            assert_eq!(cell.symbol(), " ", "{x},{y}");
+        }
+    }
+    assert!(buffer.content.iter().any(|cell| cell.symbol() != " "));
+}
+
+#[test]
+fn state_keeps_particles_between_frames() {
+    let area = Rect::new(0, 0, 40, 16);
+    let mut state = HeartState::new(3);
+    for tick in 1..=20 {
+        let heart = HeartWidget::new()
+            .colors([Color::Red])
+            .seconds(f64::from(tick) * 0.1);
+        heart.update(&mut state, 0.1);
+    }
+    let config = Config::default();
+    let bounds = state.world_bounds(&config);
+    state.particles_mut().confetti(&config, bounds);
+
+    let mut buffer = Buffer::empty(area);
+    let heart = HeartWidget::new().seconds(2.0);
+    StatefulWidget::render(heart, area, &mut buffer, &mut state);
+    assert!(buffer.content.iter().any(|cell| cell.symbol() == "♥"));
+}
+
+#[test]
+fn the_color_depth_is_detected_once_when_built() {
+    let heart = HeartWidget::new().config(Config::default());
+    assert_ne!(heart.settings().color_depth, ColorDepth::Auto);
+    assert_ne!(HeartWidget::new().settings().color_depth, ColorDepth::Auto);
+    // An unresolved depth draws colors as they are
+    assert_eq!(
+        ColorDepth::Auto.quantize(Color::Rgb(1, 2, 3)),
+        Color::Rgb(1, 2, 3)
+    );
+}